@@ -0,0 +1,309 @@
+// gas 基准：在 gtest 中部署游戏会话程序和 Wordle 程序，测量不同会话数量和单词包大小下各动作的 gas 消耗，
+// 与保存的结果比较，超过回归阈值时失败
+use anyhow::{anyhow, bail, Context, Result};
+use gsession_io::*;
+use gtest::{Gas, Program, RunResult, System};
+use gword_io::{WordleInit, WordleState};
+use std::{collections::BTreeMap, fs, path::Path};
+
+const USAGE: &str = "\
+Usage: gword-bench [OPTIONS]
+
+Build the programs with the generated word packs first:
+  cargo build -r -p gsession -p gword --features gword/bench-packs
+
+Options:
+  --session <PATH>      gsession wasm [default: target/wasm32-unknown-unknown/release/gsession.opt.wasm]
+  --wordle <PATH>       gword wasm [default: target/wasm32-unknown-unknown/release/gword.opt.wasm]
+  --scenario <NAME>     Only run scenarios whose name contains NAME
+  --baseline <PATH>     Compare with results saved by --save
+  --threshold <PERCENT> Fail when gas grows by more than PERCENT over the baseline [default: 5]
+  --save <PATH>         Save the results as the new baseline
+  -h, --help            Print help";
+
+// 部署者
+const ADMIN: u64 = 1;
+// 填充会话的用户从 FIRST_USER 开始
+const FIRST_USER: u64 = 1_000;
+// 测量的用户，每个场景重复测量 SAMPLES 次取平均
+const MEASURED_USER: u64 = 100;
+const SAMPLES: u64 = 10;
+// 测量 CheckWord 时的猜测
+const GUESS: &str = "zzzzz";
+
+// 场景：已有的会话数量（包括测量的用户）和使用的单词包
+struct Scenario {
+    name: &'static str,
+    sessions: u64,
+    pack: &'static str,
+}
+
+const SCENARIOS: [Scenario; 6] = [
+    Scenario {
+        name: "1 session",
+        sessions: 1,
+        pack: "classic",
+    },
+    Scenario {
+        name: "1k sessions",
+        sessions: 1_000,
+        pack: "classic",
+    },
+    Scenario {
+        name: "100k sessions",
+        sessions: 100_000,
+        pack: "classic",
+    },
+    Scenario {
+        name: "1k words",
+        sessions: 1,
+        pack: "bench-1k",
+    },
+    Scenario {
+        name: "10k words",
+        sessions: 1,
+        pack: "bench-10k",
+    },
+    Scenario {
+        name: "1k sessions, 10k words",
+        sessions: 1_000,
+        pack: "bench-10k",
+    },
+];
+
+// 测量的动作
+const ACTIONS: [&str; 3] = ["StartGame", "CheckWord", "GiveUp"];
+
+// 一个动作的 gas：会话程序处理消息（包括被唤醒后继续执行），
+// 以及等待回复的过程（Wordle 程序处理请求和会话程序的 handle_reply）
+#[derive(Debug, Default, Clone, Copy)]
+struct Measurement {
+    handle: u64,
+    reply: u64,
+}
+
+impl Measurement {
+    fn total(&self) -> u64 {
+        self.handle + self.reply
+    }
+}
+
+// 命令行参数
+struct Options {
+    session_wasm: String,
+    wordle_wasm: String,
+    scenario: Option<String>,
+    baseline: Option<String>,
+    threshold: f64,
+    save: Option<String>,
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options> {
+    let mut options = Options {
+        session_wasm: "target/wasm32-unknown-unknown/release/gsession.opt.wasm".into(),
+        wordle_wasm: "target/wasm32-unknown-unknown/release/gword.opt.wasm".into(),
+        scenario: None,
+        baseline: None,
+        threshold: 5.0,
+        save: None,
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .with_context(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--session" => options.session_wasm = value()?,
+            "--wordle" => options.wordle_wasm = value()?,
+            "--scenario" => options.scenario = Some(value()?),
+            "--baseline" => options.baseline = Some(value()?),
+            "--threshold" => options.threshold = value()?.parse().context("Invalid --threshold")?,
+            "--save" => options.save = Some(value()?),
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            _ => bail!("Unknown argument: {arg}\n\n{USAGE}"),
+        }
+    }
+    Ok(options)
+}
+
+fn main() -> Result<()> {
+    let options = parse_args(std::env::args().skip(1).collect())?;
+    for path in [&options.session_wasm, &options.wordle_wasm] {
+        if !Path::new(path).exists() {
+            bail!("{path} not found\n\n{USAGE}");
+        }
+    }
+    let baseline = match &options.baseline {
+        Some(path) => load_results(path)?,
+        None => BTreeMap::new(),
+    };
+
+    let mut results = BTreeMap::new();
+    println!(
+        "{:<24} {:<10} {:>12} {:>12} {:>12} {:>12} {:>8}",
+        "scenario", "action", "handle", "reply", "total", "baseline", "change"
+    );
+    let mut regressions = Vec::new();
+    for scenario in &SCENARIOS {
+        if let Some(filter) = &options.scenario {
+            if !scenario.name.contains(filter.as_str()) {
+                continue;
+            }
+        }
+        let measurements = run_scenario(&options, scenario)
+            .with_context(|| format!("Scenario failed: {}", scenario.name))?;
+        for (action, measurement) in ACTIONS.iter().zip(measurements) {
+            let key = format!("{}/{action}", scenario.name);
+            let (expected, change) = match baseline.get(&key) {
+                Some(&expected) => {
+                    let change = (measurement.total() as f64 / expected as f64 - 1.0) * 100.0;
+                    if change > options.threshold {
+                        let total = measurement.total();
+                        regressions.push(format!("{key}: {expected} -> {total}"));
+                    }
+                    (expected.to_string(), format!("{change:+.1}%"))
+                }
+                None => ("-".into(), "-".into()),
+            };
+            println!(
+                "{:<24} {action:<10} {:>12} {:>12} {:>12} {expected:>12} {change:>8}",
+                scenario.name,
+                measurement.handle,
+                measurement.reply,
+                measurement.total()
+            );
+            results.insert(key, measurement.total());
+        }
+    }
+
+    if let Some(path) = &options.save {
+        save_results(path, &results)?;
+    }
+    if !regressions.is_empty() {
+        bail!(
+            "Gas grew by more than {}%:\n{}",
+            options.threshold,
+            regressions.join("\n")
+        );
+    }
+    Ok(())
+}
+
+// 部署程序，填充会话后测量每个动作的平均 gas
+fn run_scenario(options: &Options, scenario: &Scenario) -> Result<Vec<Measurement>> {
+    let system = System::new();
+    let wordle = Program::from_file(&system, &options.wordle_wasm);
+    let session = Program::from_file(&system, &options.session_wasm);
+    check(
+        wordle.send(
+            ADMIN,
+            WordleInit {
+                session_program_id: session.id(),
+                seed: None,
+            },
+        ),
+        "Wordle init",
+    )?;
+    check(
+        session.send(
+            ADMIN,
+            GameSessionInit {
+                wordle_program_id: wordle.id(),
+                config: GameSessionConfig::default(),
+            },
+        ),
+        "GameSession init",
+    )?;
+
+    let state: WordleState = wordle
+        .read_state(b"")
+        .map_err(|error| anyhow!("Unable to read Wordle state: {error:?}"))?;
+    let pack = state
+        .packs
+        .iter()
+        .find(|pack| pack.name == scenario.pack)
+        .with_context(|| {
+            format!(
+                "Word pack {} not found, build gword with --features bench-packs",
+                scenario.pack
+            )
+        })?
+        .id;
+
+    // 测量的用户也占一个会话
+    for user in FIRST_USER..FIRST_USER + scenario.sessions - 1 {
+        check(
+            session.send(user, GameSessionAction::StartGameWithPack { pack }),
+            "StartGame",
+        )?;
+    }
+
+    let mut total = vec![Measurement::default(); ACTIONS.len()];
+    for _ in 0..SAMPLES {
+        let actions = [
+            GameSessionAction::StartGameWithPack { pack },
+            GameSessionAction::CheckWord { word: GUESS.into() },
+            GameSessionAction::GiveUp,
+        ];
+        for (i, action) in actions.into_iter().enumerate() {
+            let res = check(session.send(MEASURED_USER, action), ACTIONS[i])?;
+            total[i].handle += gas(res.main_gas_burned());
+            total[i].reply += res
+                .others_gas_burned()
+                .values()
+                .copied()
+                .map(gas)
+                .sum::<u64>();
+        }
+    }
+    Ok(total
+        .into_iter()
+        .map(|measurement| Measurement {
+            handle: measurement.handle / SAMPLES,
+            reply: measurement.reply / SAMPLES,
+        })
+        .collect())
+}
+
+// 检查消息处理成功
+fn check(res: RunResult, action: &str) -> Result<RunResult> {
+    if res.main_failed() || res.others_failed() {
+        bail!("{action} failed: {:?}", res.log());
+    }
+    Ok(res)
+}
+
+// gtest 的 Gas 只能通过 Display 取得数值
+fn gas(gas: Gas) -> u64 {
+    gas.to_string().parse().expect("Gas is not a number")
+}
+
+// 结果文件每行为 "场景/动作<TAB>gas"
+fn load_results(path: &str) -> Result<BTreeMap<String, u64>> {
+    let content = fs::read_to_string(path).with_context(|| format!("Unable to read {path}"))?;
+    content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (key, gas) = line
+                .split_once('\t')
+                .with_context(|| format!("Invalid line in {path}: {line}"))?;
+            let gas = gas
+                .parse()
+                .with_context(|| format!("Invalid gas in {path}: {line}"))?;
+            Ok((key.to_string(), gas))
+        })
+        .collect()
+}
+
+fn save_results(path: &str, results: &BTreeMap<String, u64>) -> Result<()> {
+    let content: String = results
+        .iter()
+        .map(|(key, gas)| format!("{key}\t{gas}\n"))
+        .collect();
+    fs::write(path, content).with_context(|| format!("Unable to write {path}"))
+}
@@ -0,0 +1,336 @@
+// 在 gtest 模拟器中运行游戏会话程序和 Wordle 程序，在终端中游玩，用于不依赖节点复现问题
+use anyhow::{bail, Context, Result};
+use gsession_io::*;
+use gtest::System;
+use gword_client::{Error, GameClient, SystemClient};
+use gword_core::{word_pack, PackId, Tile, WORD_LENGTH, WORD_PACKS};
+use gword_solver::{parse_feedback, Solver};
+use std::io::{self, BufRead, Write};
+
+const USAGE: &str = "\
+Usage: gword-cli [OPTIONS]
+       gword-cli solve [--pack PACK] [WORD:FEEDBACK]...
+
+Options:
+  --session <PATH>        gsession wasm [default: target/wasm32-unknown-unknown/release/gsession.opt.wasm]
+  --wordle <PATH>         gword wasm [default: target/wasm32-unknown-unknown/release/gword.opt.wasm]
+  --move-timeout <BLOCKS> Enable the per-move timeout, losing a try on expiry
+  --hint-price <VALUE>    Price of a paid hint
+  -h, --help              Print help
+
+Solve:
+  Suggests the next guess from the feedback so far, e.g. `solve --pack animals eagle:yg.yy`,
+  where g marks a correct position, y a letter elsewhere in the word and . a missing letter";
+
+const HELP: &str = "\
+Commands:
+  start [PACK]   Start a game with the word pack (default: classic)
+  <word>         Guess a word
+  hint [pay]     Reveal a letter for a try, or for the hint price
+  giveup         Give up the current game
+  restart        Give up and start a new game
+  protocol <VER> Select the feedback encoding, v1 (positions) or v2 (packed)
+  wait <BLOCKS>  Advance blocks to trigger delayed checks
+  timeout        Advance blocks until the game times out
+  state          Show the session
+  stats          Show player stats and global analytics
+  packs          List word packs
+  suggest        Suggest the next guess for the current game
+  help           Show this help
+  quit           Exit";
+
+// 玩家账户，同时是程序的管理员
+const USER: u64 = 42;
+// 付费提示时为用户充值的余额
+const USER_BALANCE: u128 = 1_000_000_000_000_000;
+
+// 提示的建议数量
+const SUGGESTIONS: usize = 5;
+
+// 命令行参数
+struct Options {
+    session_wasm: String,
+    wordle_wasm: String,
+    config: GameSessionConfig,
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options> {
+    let mut options = Options {
+        session_wasm: "target/wasm32-unknown-unknown/release/gsession.opt.wasm".into(),
+        wordle_wasm: "target/wasm32-unknown-unknown/release/gword.opt.wasm".into(),
+        config: GameSessionConfig {
+            max_hints: 1,
+            ..Default::default()
+        },
+    };
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .with_context(|| format!("Missing value for {arg}"))
+        };
+        match arg.as_str() {
+            "--session" => options.session_wasm = value()?,
+            "--wordle" => options.wordle_wasm = value()?,
+            "--move-timeout" => {
+                options.config.move_timeout = Some(MoveTimeout {
+                    blocks: value()?.parse().context("Invalid --move-timeout")?,
+                    penalty: MoveTimeoutPenalty::LoseTry,
+                })
+            }
+            "--hint-price" => {
+                options.config.hint_price = value()?.parse().context("Invalid --hint-price")?
+            }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            _ => bail!("Unknown argument: {arg}\n\n{USAGE}"),
+        }
+    }
+    Ok(options)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("solve") {
+        return solve(&args[1..]);
+    }
+    let options = parse_args(args)?;
+    let system = System::new();
+    system.mint_to(USER, USER_BALANCE);
+    let hint_price = options.config.hint_price;
+    let mut client = SystemClient::deploy(
+        &system,
+        &options.session_wasm,
+        &options.wordle_wasm,
+        USER,
+        options.config,
+    )
+    .context("Unable to deploy the programs")?;
+
+    println!("Type `help` for commands, or `start` to play.");
+    let mut last_word = String::new();
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("[block {}] > ", system.block_height());
+        io::stdout().flush()?;
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let line = line?;
+        let mut words = line.split_whitespace();
+        let Some(command) = words.next() else {
+            continue;
+        };
+        let argument = words.next();
+        let result = match command {
+            "start" => match parse_pack(argument) {
+                Ok(pack) => client.start_game(pack).await,
+                Err(error) => {
+                    println!("{error}");
+                    continue;
+                }
+            },
+            "hint" if argument == Some("pay") => {
+                client.request_hint(HintPayment::Value, hint_price).await
+            }
+            "hint" => client.request_hint(HintPayment::Try, 0).await,
+            "giveup" => client.give_up().await,
+            "restart" => client.restart().await,
+            "protocol" => match argument {
+                Some("v1") => client.negotiate_protocol(&[ProtocolVersion::V1]).await,
+                Some("v2") => client.negotiate_protocol(&ProtocolVersion::SUPPORTED).await,
+                _ => {
+                    println!("Usage: protocol <v1|v2>");
+                    continue;
+                }
+            },
+            "wait" => {
+                match argument.map(str::parse) {
+                    Some(Ok(blocks)) => client.advance_blocks(blocks),
+                    _ => println!("Usage: wait <BLOCKS>"),
+                }
+                print_messages(&mut client, &last_word).await;
+                continue;
+            }
+            "timeout" => {
+                client.advance_blocks(GAME_TIMEOUT_BLOCKS);
+                print_messages(&mut client, &last_word).await;
+                continue;
+            }
+            "state" => {
+                match client.session_of(client.user()).await {
+                    Ok(Some(session_info)) => println!("{session_info:#?}"),
+                    Ok(None) => println!("No session yet"),
+                    Err(error) => println!("{error}"),
+                }
+                continue;
+            }
+            "stats" => {
+                match (client.stats().await, client.analytics().await) {
+                    (Ok(stats), Ok(analytics)) => {
+                        println!("{stats:#?}");
+                        println!("{:#?}", analytics.totals);
+                    }
+                    (Err(error), _) | (_, Err(error)) => println!("{error}"),
+                }
+                continue;
+            }
+            "packs" => {
+                for (pack, word_pack) in WORD_PACKS.iter().enumerate() {
+                    println!(
+                        "{pack}: {} ({:?}, {} words)",
+                        word_pack.name,
+                        word_pack.difficulty,
+                        word_pack.words.len()
+                    );
+                }
+                continue;
+            }
+            "suggest" => {
+                match client.session_of(client.user()).await {
+                    Ok(Some(session_info)) if session_info.mode == WordleMode::Classic => {
+                        let words = word_pack(session_info.pack).map_or(&[][..], |pack| pack.words);
+                        print_suggestions(&Solver::from_session(words, &session_info));
+                    }
+                    Ok(_) => println!("Suggestions need a classic game"),
+                    Err(error) => println!("{error}"),
+                }
+                continue;
+            }
+            "help" => {
+                println!("{HELP}");
+                continue;
+            }
+            "quit" | "exit" => break,
+            word if word.len() == WORD_LENGTH => {
+                last_word = word.to_lowercase();
+                client.check_word(&last_word).await
+            }
+            _ => {
+                println!("Unknown command, type `help`");
+                continue;
+            }
+        };
+        match result {
+            Ok(event) => print_event(&event, &last_word),
+            Err(Error::Panicked(message)) => println!("Rejected: {message}"),
+            Err(error) => return Err(error.into()),
+        }
+        print_messages(&mut client, &last_word).await;
+    }
+    Ok(())
+}
+
+// 单词包可以用ID或名称指定
+fn parse_pack(argument: Option<&str>) -> Result<PackId> {
+    let Some(argument) = argument else {
+        return Ok(DEFAULT_PACK);
+    };
+    if let Ok(pack) = argument.parse::<PackId>() {
+        if word_pack(pack).is_some() {
+            return Ok(pack);
+        }
+    }
+    WORD_PACKS
+        .iter()
+        .position(|word_pack| word_pack.name == argument)
+        .map(|pack| pack as PackId)
+        .with_context(|| format!("Unknown word pack: {argument}, see `packs`"))
+}
+
+// 输出程序直接发给用户的消息，例如超时
+async fn print_messages(client: &mut SystemClient<'_>, last_word: &str) {
+    while let Ok(event) = client.next_message().await {
+        print_event(&event, last_word);
+    }
+}
+
+fn print_event(event: &GameSessionEvent, last_word: &str) {
+    match event {
+        GameSessionEvent::GameOver(GameStatus::Win) => {
+            println!("{}", render_tiles(last_word, PackedFeedback::SOLVED));
+            println!("You won!");
+        }
+        GameSessionEvent::GameOver(GameStatus::Lose) => println!("Game over, you lost"),
+        GameSessionEvent::GaveUp { word: Some(word) } => println!("The word was {word}"),
+        GameSessionEvent::MoveTimedOut { tries } => {
+            println!("Move timed out, tries used: {tries}")
+        }
+        GameSessionEvent::HintRevealed { position, letter } => println!(
+            "Letter {} is {}",
+            position + 1,
+            char::from(*letter).to_ascii_uppercase()
+        ),
+        // 两个协议版本的猜词结果
+        event => match event.feedback() {
+            Some(feedback) => println!("{}", render_tiles(last_word, feedback)),
+            None => println!("{event:?}"),
+        },
+    }
+}
+
+// 用 ANSI 背景色渲染每个字母：绿色为位置正确，黄色为包含在单词中，灰色为不包含
+fn render_tiles(word: &str, feedback: PackedFeedback) -> String {
+    word.chars()
+        .enumerate()
+        .map(|(i, letter)| {
+            let color = match feedback.tile(i) {
+                Tile::Correct => 42,
+                Tile::Present => 43,
+                Tile::Absent => 100,
+            };
+            format!("\x1b[1;30;{color}m {} \x1b[0m", letter.to_ascii_uppercase())
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// 离线求解：根据命令行给出的猜词和反馈给出建议
+fn solve(args: &[String]) -> Result<()> {
+    let mut pack = DEFAULT_PACK;
+    let mut clues = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--pack" {
+            pack = parse_pack(args.next().map(String::as_str))?;
+            continue;
+        }
+        let (word, feedback) = arg
+            .split_once(':')
+            .with_context(|| format!("Expected WORD:FEEDBACK, got {arg}"))?;
+        let feedback =
+            parse_feedback(feedback).with_context(|| format!("Invalid feedback in {arg}"))?;
+        clues.push((word.to_lowercase(), feedback));
+    }
+    let words = word_pack(pack).map_or(&[][..], |pack| pack.words);
+    let mut solver = Solver::new(words);
+    for (word, feedback) in &clues {
+        solver.apply_guess(word, feedback);
+    }
+    print_suggestions(&solver);
+    Ok(())
+}
+
+fn print_suggestions(solver: &Solver) {
+    let candidates = solver.candidates();
+    match candidates {
+        [] => return println!("No word matches the feedback"),
+        [word] => return println!("The word is {word}"),
+        _ => println!("{} candidates: {}", candidates.len(), candidates.join(", ")),
+    }
+    for suggestion in solver.rank().iter().take(SUGGESTIONS) {
+        println!(
+            "{} {:.2} bits{}",
+            suggestion.word,
+            suggestion.entropy,
+            if suggestion.candidate {
+                ""
+            } else {
+                " (not a candidate)"
+            }
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use super::*;
+use gclient::{
+    metadata::runtime_types::{
+        gear_core::message::{common::ReplyDetails, user::UserMessage},
+        gear_core_errors::simple::ReplyCode,
+    },
+    Event as NodeEvent, EventListener, EventProcessor, GearApi, GearEvent,
+};
+use gstd::MessageId;
+use std::collections::VecDeque;
+
+// 通过节点访问程序的客户端
+pub struct GearApiClient {
+    api: GearApi,
+    listener: EventListener,
+    session_program_id: ActorId,
+    wordle_program_id: ActorId,
+    messages: VecDeque<GameSessionEvent>, // 等待回复时收到的发给用户的消息
+}
+
+// 节点上与用户相关的消息
+enum UserEvent {
+    Reply(Vec<u8>, bool), // 回复的内容，以及是否执行成功
+    Message(GameSessionEvent),
+}
+
+impl GearApiClient {
+    // 使用 api 的账户连接已部署的程序
+    pub async fn new(
+        api: GearApi,
+        session_program_id: ActorId,
+        wordle_program_id: ActorId,
+    ) -> Result<Self> {
+        let listener = api.subscribe().await.map_err(Error::Node)?;
+        Ok(Self {
+            api,
+            listener,
+            session_program_id,
+            wordle_program_id,
+            messages: VecDeque::new(),
+        })
+    }
+
+    // 底层的 GearApi
+    pub fn api(&self) -> &GearApi {
+        &self.api
+    }
+
+    fn program_id(&self, program: ProgramKind) -> ActorId {
+        match program {
+            ProgramKind::Session => self.session_program_id,
+            ProgramKind::Wordle => self.wordle_program_id,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl GameClient for GearApiClient {
+    fn user(&self) -> ActorId {
+        ActorId::new(self.api.account_id().clone().into())
+    }
+
+    async fn send_bytes(
+        &mut self,
+        program: ProgramKind,
+        payload: Vec<u8>,
+        value: u128,
+    ) -> Result<Vec<u8>> {
+        let program_id = self.program_id(program);
+        let gas_info = self
+            .api
+            .calculate_handle_gas(None, program_id, payload.clone(), value, true)
+            .await
+            .map_err(Error::Node)?;
+        let (message_id, _) = self
+            .api
+            .send_message_bytes(program_id, payload, gas_info.min_limit, value)
+            .await
+            .map_err(Error::Node)?;
+        // 等待回复，同时保存期间收到的发给用户的消息
+        let (user, session_program_id) = (self.user(), self.session_program_id);
+        let events = self
+            .listener
+            .proc_many(
+                |event| user_event(event, user, session_program_id, Some(message_id)),
+                |events| {
+                    let replied = events
+                        .iter()
+                        .any(|event| matches!(event, UserEvent::Reply(..)));
+                    (events, replied)
+                },
+            )
+            .await
+            .map_err(Error::Node)?;
+        let mut reply = None;
+        for event in events {
+            match event {
+                UserEvent::Reply(payload, succeed) => reply = Some((payload, succeed)),
+                UserEvent::Message(event) => self.messages.push_back(event),
+            }
+        }
+        match reply {
+            Some((payload, true)) => Ok(payload),
+            Some((payload, false)) => {
+                Err(Error::Panicked(String::from_utf8_lossy(&payload).into()))
+            }
+            None => Err(Error::NoMessage),
+        }
+    }
+
+    async fn read_state_bytes(&self, program: ProgramKind, payload: Vec<u8>) -> Result<Vec<u8>> {
+        self.api
+            .read_state_bytes(self.program_id(program), payload)
+            .await
+            .map_err(Error::Node)
+    }
+
+    async fn next_message(&mut self) -> Result<GameSessionEvent> {
+        if let Some(event) = self.messages.pop_front() {
+            return Ok(event);
+        }
+        let (user, session_program_id) = (self.user(), self.session_program_id);
+        let event = self
+            .listener
+            .proc(
+                |event| match user_event(event, user, session_program_id, None) {
+                    Some(UserEvent::Message(event)) => Some(event),
+                    _ => None,
+                },
+            )
+            .await
+            .map_err(Error::Node)?;
+        Ok(event)
+    }
+}
+
+// 识别对 reply_to 的回复，以及游戏会话程序发给用户的消息
+fn user_event(
+    event: NodeEvent,
+    user: ActorId,
+    session_program_id: ActorId,
+    reply_to: Option<MessageId>,
+) -> Option<UserEvent> {
+    let NodeEvent::Gear(GearEvent::UserMessageSent {
+        message:
+            UserMessage {
+                source,
+                destination,
+                payload,
+                details,
+                ..
+            },
+        ..
+    }) = event
+    else {
+        return None;
+    };
+    if ActorId::new(destination.0) != user {
+        return None;
+    }
+    match details {
+        Some(ReplyDetails { to, code }) => (Some(MessageId::new(to.0)) == reply_to)
+            .then(|| UserEvent::Reply(payload.0, matches!(code, ReplyCode::Success(..)))),
+        None if ActorId::new(source.0) == session_program_id => {
+            GameSessionEvent::decode(&mut &payload.0[..])
+                .ok()
+                .map(UserEvent::Message)
+        }
+        None => None,
+    }
+}
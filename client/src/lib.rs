@@ -0,0 +1,218 @@
+// 游戏会话程序和 Wordle 程序的客户端：GearApiClient 通过节点访问，SystemClient 在 gtest 模拟器中运行
+use async_trait::async_trait;
+use gsession_io::*;
+use gstd::ActorId;
+use gword_io::{challenge_commitment, Action, ChallengeTarget, Event, WordleInit, WordleState};
+use parity_scale_codec::{Decode, Encode};
+use std::fmt;
+
+#[cfg(feature = "gclient")]
+mod gear_api;
+#[cfg(feature = "gtest")]
+mod system;
+
+#[cfg(feature = "gclient")]
+pub use gear_api::GearApiClient;
+#[cfg(feature = "gtest")]
+pub use system::SystemClient;
+
+// 客户端错误
+#[derive(Debug)]
+pub enum Error {
+    #[cfg(feature = "gclient")]
+    Node(gclient::Error), // 节点或网络错误
+    #[cfg(feature = "gtest")]
+    Simulator(gtest::TestError), // 模拟器错误
+    Panicked(String),                                   // 程序执行失败，包含错误信息
+    Decode(parity_scale_codec::Error),                  // 无法解码回复或状态
+    UnexpectedReply(Box<dyn fmt::Debug + Send + Sync>), // 回复与请求不符
+    NoMessage,                                          // 没有发给用户的消息
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "gclient")]
+            Error::Node(error) => write!(f, "Node error: {error}"),
+            #[cfg(feature = "gtest")]
+            Error::Simulator(error) => write!(f, "Simulator error: {error}"),
+            Error::Panicked(message) => write!(f, "Program panicked: {message}"),
+            Error::Decode(error) => write!(f, "Unable to decode: {error}"),
+            Error::UnexpectedReply(reply) => write!(f, "Unexpected reply: {reply:?}"),
+            Error::NoMessage => write!(f, "There are no messages for the user"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<parity_scale_codec::Error> for Error {
+    fn from(error: parity_scale_codec::Error) -> Self {
+        Error::Decode(error)
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+// 客户端访问的程序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramKind {
+    Session, // 游戏会话程序
+    Wordle,  // Wordle 程序
+}
+
+// 两个程序的类型化客户端，实现者只需提供按字节收发消息和读取状态的方法
+#[async_trait(?Send)]
+pub trait GameClient {
+    // 发送消息的用户
+    fn user(&self) -> ActorId;
+
+    // 发送消息并返回回复；程序执行失败时返回 Error::Panicked
+    async fn send_bytes(
+        &mut self,
+        program: ProgramKind,
+        payload: Vec<u8>,
+        value: u128,
+    ) -> Result<Vec<u8>>;
+
+    // 读取程序状态
+    async fn read_state_bytes(&self, program: ProgramKind, payload: Vec<u8>) -> Result<Vec<u8>>;
+
+    // 游戏会话程序直接发给用户的下一条消息，例如超时或比赛的下一个单词
+    async fn next_message(&mut self) -> Result<GameSessionEvent>;
+
+    // 向游戏会话程序发送动作
+    async fn send(&mut self, action: GameSessionAction, value: u128) -> Result<GameSessionEvent> {
+        let reply = self
+            .send_bytes(ProgramKind::Session, action.encode(), value)
+            .await?;
+        Ok(GameSessionEvent::decode(&mut &reply[..])?)
+    }
+
+    // 开始游戏
+    async fn start_game(&mut self, pack: PackId) -> Result<GameSessionEvent> {
+        self.send(GameSessionAction::StartGameWithPack { pack }, 0)
+            .await
+    }
+
+    // 猜单词
+    async fn check_word(&mut self, word: &str) -> Result<GameSessionEvent> {
+        let word = word.to_string();
+        self.send(GameSessionAction::CheckWord { word }, 0).await
+    }
+
+    // 请求提示，付费提示需要附带提示的价格
+    async fn request_hint(
+        &mut self,
+        payment: HintPayment,
+        value: u128,
+    ) -> Result<GameSessionEvent> {
+        self.send(GameSessionAction::RequestHint { payment }, value)
+            .await
+    }
+
+    // 放弃游戏
+    async fn give_up(&mut self) -> Result<GameSessionEvent> {
+        self.send(GameSessionAction::GiveUp, 0).await
+    }
+
+    // 放弃当前游戏并开始新游戏
+    async fn restart(&mut self) -> Result<GameSessionEvent> {
+        self.send(GameSessionAction::Restart, 0).await
+    }
+
+    // 协商协议版本，返回程序选择的版本
+    async fn negotiate_protocol(
+        &mut self,
+        versions: &[ProtocolVersion],
+    ) -> Result<GameSessionEvent> {
+        let versions = versions.to_vec();
+        self.send(GameSessionAction::NegotiateProtocol { versions }, 0)
+            .await
+    }
+
+    // 创建挑战，只发送单词和盐的承诺，由用户直接发送给 Wordle 程序，返回挑战ID
+    async fn create_challenge(
+        &mut self,
+        word: &str,
+        salt: [u8; 32],
+        target: ChallengeTarget,
+    ) -> Result<u32> {
+        let action = Action::CreateChallenge {
+            commitment: challenge_commitment(word, &salt),
+            target,
+        };
+        let reply = self
+            .send_bytes(ProgramKind::Wordle, action.encode(), 0)
+            .await?;
+        match Event::decode(&mut &reply[..])? {
+            Event::ChallengeCreated { challenge_id, .. } => Ok(challenge_id),
+            event => Err(Error::UnexpectedReply(Box::new(event))),
+        }
+    }
+
+    // 公开挑战的单词和盐，之后玩家才能参加挑战
+    async fn open_challenge(
+        &mut self,
+        challenge_id: u32,
+        word: &str,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        let action = Action::OpenChallenge {
+            challenge_id,
+            word: word.to_string(),
+            salt,
+        };
+        let reply = self
+            .send_bytes(ProgramKind::Wordle, action.encode(), 0)
+            .await?;
+        match Event::decode(&mut &reply[..])? {
+            Event::ChallengeOpened { .. } => Ok(()),
+            event => Err(Error::UnexpectedReply(Box::new(event))),
+        }
+    }
+
+    // 游戏会话程序的完整状态
+    async fn state(&self) -> Result<GameSessionState> {
+        self.query(ProgramKind::Session, Vec::new()).await
+    }
+
+    // 全局游戏统计
+    async fn analytics(&self) -> Result<GameAnalytics> {
+        self.query(ProgramKind::Session, StateQuery::Analytics.encode())
+            .await
+    }
+
+    // 查询程序的状态
+    async fn query<T: Decode>(&self, program: ProgramKind, payload: Vec<u8>) -> Result<T> {
+        let state = self.read_state_bytes(program, payload).await?;
+        Ok(T::decode(&mut &state[..])?)
+    }
+
+    // 用户的游戏会话
+    async fn session_of(&self, user: ActorId) -> Result<Option<SessionInfo>> {
+        Ok(self
+            .state()
+            .await?
+            .game_sessions
+            .into_iter()
+            .find_map(|(id, session_info)| (id == user).then_some(session_info)))
+    }
+
+    // 当前用户的统计，没有玩过游戏时为默认值
+    async fn stats(&self) -> Result<PlayerStats> {
+        let user = self.user();
+        Ok(self
+            .state()
+            .await?
+            .player_stats
+            .into_iter()
+            .find_map(|(id, stats)| (id == user).then_some(stats))
+            .unwrap_or_default())
+    }
+
+    // Wordle 程序的状态：单词包和单词统计
+    async fn wordle_state(&self) -> Result<WordleState> {
+        self.query(ProgramKind::Wordle, Vec::new()).await
+    }
+}
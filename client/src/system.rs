@@ -0,0 +1,158 @@
+use super::*;
+use gtest::{Program, RunResult, System};
+use std::{collections::VecDeque, path::Path};
+
+// 在 gtest 模拟器中运行的客户端，用于离线测试；同一个 System 可以有多个用户的客户端
+pub struct SystemClient<'a> {
+    system: &'a System,
+    session_program_id: ActorId,
+    wordle_program_id: ActorId,
+    user: u64,
+    messages: VecDeque<GameSessionEvent>, // 尚未读取的发给用户的消息
+}
+
+impl<'a> SystemClient<'a> {
+    // 部署并初始化两个程序，admin 成为游戏会话程序的管理员
+    pub fn deploy(
+        system: &'a System,
+        session_wasm: impl AsRef<Path>,
+        wordle_wasm: impl AsRef<Path>,
+        admin: u64,
+        config: GameSessionConfig,
+    ) -> Result<Self> {
+        // 两个程序互相需要对方的ID，先上传再初始化
+        let wordle = Program::from_file(system, wordle_wasm);
+        let session = Program::from_file(system, session_wasm);
+        check_init(wordle.send(
+            admin,
+            WordleInit {
+                session_program_id: session.id(),
+                seed: None,
+            },
+        ))?;
+        check_init(session.send(
+            admin,
+            GameSessionInit {
+                wordle_program_id: wordle.id(),
+                config,
+            },
+        ))?;
+        Ok(Self::new(system, session.id(), wordle.id(), admin))
+    }
+
+    // 连接已部署的程序
+    pub fn new(
+        system: &'a System,
+        session_program_id: ActorId,
+        wordle_program_id: ActorId,
+        user: u64,
+    ) -> Self {
+        Self {
+            system,
+            session_program_id,
+            wordle_program_id,
+            user,
+            messages: VecDeque::new(),
+        }
+    }
+
+    // 同一组程序的其他用户的客户端
+    pub fn for_user(&self, user: u64) -> Self {
+        Self::new(
+            self.system,
+            self.session_program_id,
+            self.wordle_program_id,
+            user,
+        )
+    }
+
+    // 模拟器
+    pub fn system(&self) -> &'a System {
+        self.system
+    }
+
+    // 推进区块以触发延迟消息，例如超时检查
+    pub fn advance_blocks(&mut self, blocks: u32) {
+        for result in self.system.spend_blocks(blocks) {
+            self.collect_messages(&result);
+        }
+    }
+
+    fn program(&self, program: ProgramKind) -> Result<Program<'a>> {
+        let program_id = match program {
+            ProgramKind::Session => self.session_program_id,
+            ProgramKind::Wordle => self.wordle_program_id,
+        };
+        self.system.get_program(program_id).ok_or(Error::Simulator(
+            gtest::TestError::ActorNotFound(program_id),
+        ))
+    }
+
+    // 保存游戏会话程序发给用户的消息（回复除外）
+    fn collect_messages(&mut self, result: &RunResult) {
+        let user = ActorId::from(self.user);
+        for log in result.log() {
+            if log.source() == self.session_program_id
+                && log.destination() == user
+                && log.reply_to().is_none()
+            {
+                if let Ok(event) = GameSessionEvent::decode(&mut log.payload()) {
+                    self.messages.push_back(event);
+                }
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl GameClient for SystemClient<'_> {
+    fn user(&self) -> ActorId {
+        self.user.into()
+    }
+
+    async fn send_bytes(
+        &mut self,
+        program: ProgramKind,
+        payload: Vec<u8>,
+        value: u128,
+    ) -> Result<Vec<u8>> {
+        let result = self
+            .program(program)?
+            .send_bytes_with_value(self.user, payload, value);
+        self.collect_messages(&result);
+        let reply = result
+            .log()
+            .iter()
+            .find(|log| log.reply_to() == Some(result.sent_message_id()))
+            .map(|log| log.payload().to_vec())
+            .unwrap_or_default();
+        if result.main_failed() {
+            return Err(Error::Panicked(String::from_utf8_lossy(&reply).into()));
+        }
+        Ok(reply)
+    }
+
+    async fn read_state_bytes(&self, program: ProgramKind, payload: Vec<u8>) -> Result<Vec<u8>> {
+        self.program(program)?
+            .read_state_bytes(payload)
+            .map_err(Error::Simulator)
+    }
+
+    async fn next_message(&mut self) -> Result<GameSessionEvent> {
+        self.messages.pop_front().ok_or(Error::NoMessage)
+    }
+}
+
+// 校验程序初始化成功
+fn check_init(result: RunResult) -> Result<()> {
+    if result.main_failed() {
+        let reply = result
+            .log()
+            .iter()
+            .find(|log| log.reply_to() == Some(result.sent_message_id()))
+            .map(|log| String::from_utf8_lossy(log.payload()).into())
+            .unwrap_or_default();
+        return Err(Error::Panicked(reply));
+    }
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use gsession_io::*;
+use gtest::System;
+use gword_client::{Error, GameClient, SystemClient};
+use gword_testing::wasm_path;
+
+// 管理员ID
+const ADMIN: u64 = 40;
+// 用户ID
+const USER: u64 = 50;
+
+fn deploy(system: &System, config: GameSessionConfig) -> SystemClient<'_> {
+    system.init_logger();
+    SystemClient::deploy(
+        system,
+        wasm_path("gsession"),
+        wasm_path("gword"),
+        ADMIN,
+        config,
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_game() {
+    let system = System::new();
+    let mut client = deploy(&system, GameSessionConfig::default()).for_user(USER);
+
+    assert!(matches!(
+        client.start_game(DEFAULT_PACK).await.unwrap(),
+        GameSessionEvent::StartSuccess
+    ));
+    let session_info = client.session_of(USER.into()).await.unwrap().unwrap();
+    assert!(matches!(
+        session_info.session_status,
+        SessionStatus::WaitUserInput
+    ));
+
+    // 无效单词使程序执行失败
+    assert!(matches!(
+        client.check_word("abc").await,
+        Err(Error::Panicked(..))
+    ));
+
+    let mut won = false;
+    for word in ["house", "human", "horse"] {
+        match client.check_word(word).await.unwrap() {
+            GameSessionEvent::GameOver(GameStatus::Win) => {
+                won = true;
+                break;
+            }
+            GameSessionEvent::CheckWordResult { .. } => {}
+            event => panic!("Unexpected event: {:?}", event),
+        }
+    }
+    assert!(won);
+
+    let stats = client.stats().await.unwrap();
+    assert_eq!((stats.games_played, stats.wins), (1, 1));
+    assert_eq!(client.analytics().await.unwrap().totals.won, 1);
+    // 没有玩过游戏的用户
+    assert_eq!(
+        client
+            .for_user(USER + 1)
+            .stats()
+            .await
+            .unwrap()
+            .games_played,
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_user_messages() {
+    let system = System::new();
+    let config = GameSessionConfig {
+        move_timeout: Some(MoveTimeout {
+            blocks: 10,
+            penalty: MoveTimeoutPenalty::LoseTry,
+        }),
+        ..Default::default()
+    };
+    let mut client = deploy(&system, config).for_user(USER);
+
+    client.start_game(DEFAULT_PACK).await.unwrap();
+    assert!(matches!(client.next_message().await, Err(Error::NoMessage)));
+
+    // 本步超时的通知由程序直接发给用户
+    client.advance_blocks(11);
+    assert!(matches!(
+        client.next_message().await.unwrap(),
+        GameSessionEvent::MoveTimedOut { tries: 1 }
+    ));
+}
@@ -0,0 +1,5 @@
+use gbot_io::BotMetadata;
+
+fn main() {
+    gear_wasm_builder::build_with_metadata::<BotMetadata>();
+}
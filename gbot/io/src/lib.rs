@@ -0,0 +1,90 @@
+#![no_std]
+
+use gmeta::*;
+use gsession_io::PackId;
+use gstd::{prelude::*, ActorId};
+
+// 机器人程序的元数据，机器人像普通用户一样通过游戏会话程序玩 Wordle
+pub struct BotMetadata;
+
+impl Metadata for BotMetadata {
+    type Init = In<BotInit>; // 游戏会话程序地址和水平
+    type Handle = InOut<BotAction, BotEvent>; // 开始或停止对局
+    type Others = (); // 无其他类型
+    type Reply = (); // 无回复消息
+    type Signal = (); // 无信号
+    type State = Out<BotState>; // 机器人状态和统计
+}
+
+// 初始化参数
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub struct BotInit {
+    pub session_program_id: ActorId, // 游戏会话程序
+    pub skill: Skill,                // 水平
+}
+
+// 机器人的水平，决定如何选择下一次猜测
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum Skill {
+    Novice, // 随机猜测尚未猜过的单词，不使用反馈
+    #[default]
+    Casual, // 随机猜测与所有反馈一致的候选单词
+    Expert, // 猜测使剩余候选单词期望数量最少的单词
+}
+
+// 机器人动作
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub enum BotAction {
+    Play {
+        pack: PackId, // 单词包
+        games: u32,   // 连续进行的局数
+    },
+    Stop,            // 当前局结束后停止
+    SetSkill(Skill), // 修改水平（仅部署者）
+}
+
+// 机器人事件
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub enum BotEvent {
+    Playing,             // 已开始对局
+    Stopping,            // 将在当前局结束后停止
+    SkillChanged(Skill), // 已修改水平
+    // 对局结束，发送给请求者
+    Finished {
+        stats: BotStats, // 本次请求的统计
+        completed: bool, // 是否完成了所有局，false 表示被停止或游戏会话程序拒绝
+    },
+}
+
+// 对局统计
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct BotStats {
+    pub games_played: u32, // 已结束的局数
+    pub wins: u32,         // 胜利次数
+    pub losses: u32,       // 失败次数
+    pub total_tries: u64,  // 胜局使用的尝试次数总和
+}
+
+impl BotStats {
+    // 记录一局的结果
+    pub fn record(&mut self, won: bool, tries: u8) {
+        self.games_played += 1;
+        if won {
+            self.wins += 1;
+            self.total_tries += tries as u64;
+        } else {
+            self.losses += 1;
+        }
+    }
+}
+
+// 机器人状态
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub struct BotState {
+    pub owner: ActorId,              // 部署者
+    pub session_program_id: ActorId, // 游戏会话程序
+    pub skill: Skill,                // 水平
+    pub requester: Option<ActorId>,  // 当前对局的请求者，None 表示空闲
+    pub games_left: u32,             // 当前请求剩余的局数（包含进行中的一局）
+    pub stats: BotStats,             // 所有对局的统计
+}
@@ -0,0 +1,279 @@
+#![no_std]
+use gbot_io::*;
+use gsession_io::{GameSessionAction, GameSessionEvent, GameStatus, PackId};
+use gstd::{exec, msg, prelude::*, ActorId, MessageId};
+use gword_core::{check_word_packed, word_pack, WORD_LENGTH};
+
+// 全局可变变量，存储机器人状态
+static mut BOT: Option<Bot> = None;
+
+// 专家每次猜测最多比较的单词对数，避免大单词包耗尽 gas
+const EXPERT_MAX_CHECKS: usize = 100_000;
+
+// 进行中的对局请求
+struct Job {
+    requester: ActorId,            // 请求者，结束时收到统计
+    pack: PackId,                  // 单词包
+    games_left: u32,               // 剩余局数（包含进行中的一局）
+    stopping: bool,                // 当前局结束后停止
+    stats: BotStats,               // 本次请求的统计
+    candidates: Vec<&'static str>, // 本局与所有反馈一致的候选单词
+    guesses: Vec<&'static str>,    // 本局已猜过的单词
+    pending: MessageId,            // 等待游戏会话程序回复的消息
+}
+
+// 机器人
+struct Bot {
+    owner: ActorId,              // 部署者，可以修改水平
+    session_program_id: ActorId, // 游戏会话程序
+    skill: Skill,                // 水平
+    job: Option<Job>,            // 进行中的请求
+    stats: BotStats,             // 所有对局的统计
+}
+
+impl Bot {
+    // 开始新的一局
+    fn start_game(&mut self) {
+        let job = self.job.as_mut().expect("The bot is not playing");
+        job.candidates = pack_words(job.pack).to_vec();
+        job.guesses.clear();
+        job.pending = msg::send(
+            self.session_program_id,
+            GameSessionAction::StartGameWithPack { pack: job.pack },
+            0,
+        )
+        .expect("Error in sending a message: StartGame");
+    }
+
+    // 选择并发送下一次猜测
+    fn guess(&mut self) {
+        let job = self.job.as_mut().expect("The bot is not playing");
+        let word = choose_word(self.skill, job);
+        job.guesses.push(word);
+        job.pending = msg::send(
+            self.session_program_id,
+            GameSessionAction::CheckWord { word: word.into() },
+            0,
+        )
+        .expect("Error in sending a message: CheckWord");
+    }
+
+    // 记录一局的结果，继续下一局或结束请求
+    fn game_over(&mut self, won: bool) {
+        let job = self.job.as_mut().expect("The bot is not playing");
+        let tries = job.guesses.len() as u8;
+        job.stats.record(won, tries);
+        self.stats.record(won, tries);
+        job.games_left -= 1;
+        if job.games_left == 0 || job.stopping {
+            self.finish();
+        } else {
+            self.start_game();
+        }
+    }
+
+    // 结束请求并将统计发送给请求者
+    fn finish(&mut self) {
+        let job = self.job.take().expect("The bot is not playing");
+        msg::send(
+            job.requester,
+            BotEvent::Finished {
+                stats: job.stats,
+                completed: job.games_left == 0,
+            },
+            0,
+        )
+        .expect("Error in sending a message: Finished");
+    }
+}
+
+// 获取可变的机器人状态，确保已初始化
+fn get_bot_mut() -> &'static mut Bot {
+    unsafe { BOT.as_mut().expect("The program is not initialized") }
+}
+
+// 单词包中的单词
+fn pack_words(pack: PackId) -> &'static [&'static str] {
+    word_pack(pack).expect("Invalid word pack").words
+}
+
+// 根据水平选择下一次猜测
+fn choose_word(skill: Skill, job: &Job) -> &'static str {
+    let unguessed: Vec<&'static str> = pack_words(job.pack)
+        .iter()
+        .copied()
+        .filter(|word| !job.guesses.contains(word))
+        .collect();
+    assert!(!unguessed.is_empty(), "There are no words to guess");
+    match skill {
+        Skill::Casual if !job.candidates.is_empty() => {
+            job.candidates[get_random_value(job.candidates.len() as u32) as usize]
+        }
+        // 只剩两个候选单词时无法更好地区分，直接猜其中一个
+        Skill::Expert if (1..=2).contains(&job.candidates.len()) => job.candidates[0],
+        Skill::Expert if !job.candidates.is_empty() => expert_guesses(&unguessed, &job.candidates)
+            .iter()
+            .copied()
+            .min_by_key(|word| {
+                (
+                    expected_candidates(word, &job.candidates),
+                    !job.candidates.contains(word),
+                )
+            })
+            .expect("There are no words to guess"),
+        // 新手，或没有候选单词时（例如单词不在单词包中）随机猜测
+        _ => unguessed[get_random_value(unguessed.len() as u32) as usize],
+    }
+}
+
+// 专家比较的猜测：单词对过多时只从候选单词的前一部分中选择
+fn expert_guesses<'a>(
+    unguessed: &'a [&'static str],
+    candidates: &'a [&'static str],
+) -> &'a [&'static str] {
+    if unguessed.len().saturating_mul(candidates.len()) <= EXPERT_MAX_CHECKS {
+        return unguessed;
+    }
+    let limit = (EXPERT_MAX_CHECKS / candidates.len()).max(1);
+    &candidates[..limit.min(candidates.len())]
+}
+
+// 猜测 guess 后剩余候选单词的期望数量，乘以候选单词数量以保持为整数
+fn expected_candidates(guess: &str, candidates: &[&str]) -> u32 {
+    let mut counts = [0u32; 1 << (2 * WORD_LENGTH)];
+    for candidate in candidates {
+        counts[check_word_packed(candidate, guess).0 as usize] += 1;
+    }
+    counts.iter().map(|count| count * count).sum()
+}
+
+#[no_mangle]
+extern "C" fn init() {
+    let init: BotInit = msg::load().expect("Unable to decode BotInit");
+    unsafe {
+        BOT = Some(Bot {
+            owner: msg::source(),
+            session_program_id: init.session_program_id,
+            skill: init.skill,
+            job: None,
+            stats: BotStats::default(),
+        });
+    }
+}
+
+#[no_mangle]
+extern "C" fn handle() {
+    let bot = get_bot_mut();
+    // 游戏会话程序直接发送的消息，例如整局超时
+    if msg::source() == bot.session_program_id {
+        let event: GameSessionEvent = msg::load().expect("Unable to decode GameSessionEvent");
+        if let GameSessionEvent::GameOver(status) = event {
+            if bot.job.is_some() {
+                bot.game_over(matches!(status, GameStatus::Win));
+            }
+        }
+        return;
+    }
+
+    let action: BotAction = msg::load().expect("Unable to decode BotAction");
+    let reply = match action {
+        BotAction::Play { pack, games } => {
+            assert!(bot.job.is_none(), "The bot is already playing");
+            assert!(games > 0, "The number of games must be positive");
+            assert!(word_pack(pack).is_some(), "Invalid word pack");
+            bot.job = Some(Job {
+                requester: msg::source(),
+                pack,
+                games_left: games,
+                stopping: false,
+                stats: BotStats::default(),
+                candidates: Vec::new(),
+                guesses: Vec::new(),
+                pending: MessageId::zero(),
+            });
+            bot.start_game();
+            BotEvent::Playing
+        }
+        BotAction::Stop => {
+            let source = msg::source();
+            let job = bot.job.as_mut().expect("The bot is not playing");
+            assert!(
+                source == job.requester || source == bot.owner,
+                "Only the requester can stop the bot"
+            );
+            job.stopping = true;
+            BotEvent::Stopping
+        }
+        BotAction::SetSkill(skill) => {
+            assert!(
+                msg::source() == bot.owner,
+                "Only the owner can change the skill"
+            );
+            bot.skill = skill;
+            BotEvent::SkillChanged(skill)
+        }
+    };
+    msg::reply(reply, 0).expect("Error in sending a reply");
+}
+
+#[no_mangle]
+extern "C" fn handle_reply() {
+    let reply_to = msg::reply_to().expect("Failed to query reply_to data");
+    let bot = get_bot_mut();
+    // 忽略过期的回复，例如超时后才收到的回复
+    let Some(job) = bot.job.as_mut() else {
+        return;
+    };
+    if reply_to != job.pending {
+        return;
+    }
+    // 游戏会话程序拒绝了动作，结束请求
+    if msg::reply_code().is_ok_and(|reply_code| reply_code.is_error()) {
+        bot.finish();
+        return;
+    }
+    let event: GameSessionEvent = msg::load().expect("Unable to decode GameSessionEvent");
+    match event {
+        GameSessionEvent::StartSuccess => bot.guess(),
+        GameSessionEvent::GameOver(status) => bot.game_over(matches!(status, GameStatus::Win)),
+        // 两个协议版本的猜词结果都可以处理
+        event => match event.feedback() {
+            Some(feedback) => {
+                // 保留与反馈一致的候选单词
+                let guess = *job.guesses.last().expect("There are no guesses");
+                job.candidates
+                    .retain(|candidate| check_word_packed(candidate, guess) == feedback);
+                bot.guess();
+            }
+            None => bot.finish(),
+        },
+    }
+}
+
+#[no_mangle]
+extern "C" fn state() {
+    let bot = unsafe { BOT.as_ref().expect("The program is not initialized") };
+    let job = bot.job.as_ref();
+    let state = BotState {
+        owner: bot.owner,
+        session_program_id: bot.session_program_id,
+        skill: bot.skill,
+        requester: job.map(|job| job.requester),
+        games_left: job.map_or(0, |job| job.games_left),
+        stats: bot.stats.clone(),
+    };
+    msg::reply(state, 0).expect("Failed to encode or reply from state");
+}
+
+// 随机数种子
+static mut SEED: u8 = 0;
+
+// 获取 [0, range) 中的随机值，range 不能为 0
+pub fn get_random_value(range: u32) -> u32 {
+    let seed = unsafe { SEED };
+    unsafe { SEED = SEED.wrapping_add(1) };
+    let mut random_input: [u8; 32] = exec::program_id().into();
+    random_input[0] = random_input[0].wrapping_add(seed);
+    let (random, _) = exec::random(random_input).expect("Error in getting random number");
+    u32::from_le_bytes([random[0], random[1], random[2], random[3]]) % range
+}
@@ -0,0 +1,5 @@
+use gmastermind_io::MastermindMetadata;
+
+fn main() {
+    gear_wasm_builder::build_with_metadata::<MastermindMetadata>();
+}
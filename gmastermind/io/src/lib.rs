@@ -0,0 +1,66 @@
+#![no_std]
+
+use gmeta::*;
+use gsession_io::{EngineAction, EngineEvent};
+use gstd::*;
+
+// 密码长度
+pub const CODE_LENGTH: usize = 4;
+// 颜色数量，每个位置的取值为 0..COLORS
+pub const COLORS: u8 = 6;
+// 尝试次数限制
+pub const TRIES_LIMIT: u8 = 10;
+
+// 定义 Mastermind 合约的元数据，使用游戏会话的通用协议
+pub struct MastermindMetadata;
+
+impl Metadata for MastermindMetadata {
+    type Init = (); // 无初始化参数
+    type Handle = InOut<EngineAction, EngineEvent>; // 通用的游戏程序协议
+    type Others = (); // 无其他类型
+    type Reply = (); // 无回复消息
+    type Signal = (); // 无信号
+    type State = (); // 无状态
+}
+
+// 玩家的一次猜测，编码后作为 EngineAction::Move 的 data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Guess(pub [u8; CODE_LENGTH]);
+
+impl Guess {
+    // 判断每个位置的颜色是否有效
+    pub fn is_valid(&self) -> bool {
+        self.0.iter().all(|color| *color < COLORS)
+    }
+}
+
+// 猜测的反馈，编码后作为 EngineEvent::MoveMade 的 data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Feedback {
+    pub black: u8, // 颜色和位置都正确的数量
+    pub white: u8, // 颜色正确但位置错误的数量
+}
+
+impl Feedback {
+    // 计算猜测相对于密码的反馈
+    pub fn new(secret: &[u8; CODE_LENGTH], guess: &[u8; CODE_LENGTH]) -> Self {
+        let black = secret.iter().zip(guess).filter(|(a, b)| a == b).count() as u8;
+        // 颜色正确的总数（不论位置）
+        let matched: u8 = (0..COLORS)
+            .map(|color| {
+                let in_secret = secret.iter().filter(|c| **c == color).count();
+                let in_guess = guess.iter().filter(|c| **c == color).count();
+                in_secret.min(in_guess) as u8
+            })
+            .sum();
+        Self {
+            black,
+            white: matched - black,
+        }
+    }
+
+    // 判断是否猜中
+    pub fn is_solved(&self) -> bool {
+        self.black as usize == CODE_LENGTH
+    }
+}
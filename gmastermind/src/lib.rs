@@ -0,0 +1,87 @@
+#![no_std]
+use gmastermind_io::*;
+use gsession_io::{EngineAction, EngineEvent, EngineResult};
+use gstd::{collections::HashMap, exec, msg, prelude::*, ActorId};
+// 全局可变变量，存储游戏状态
+static mut MASTERMIND: Option<Mastermind> = None;
+
+// 单局游戏
+struct Game {
+    secret: [u8; CODE_LENGTH], // 密码
+    tries: u8,                 // 已尝试次数
+}
+
+// Mastermind 游戏结构
+#[derive(Default)]
+struct Mastermind {
+    games: HashMap<ActorId, Game>, // 存储用户与其对应密码的映射
+}
+
+// 初始化函数，在合约部署时调用
+#[no_mangle]
+extern "C" fn init() {
+    unsafe {
+        MASTERMIND = Some(Mastermind::default());
+    }
+}
+
+// 处理消息的函数
+#[no_mangle]
+extern "C" fn handle() {
+    let action: EngineAction = msg::load().expect("Unable to decode message");
+    let mastermind = unsafe { MASTERMIND.as_mut().expect("The program is not initialized") };
+
+    let reply = match action {
+        EngineAction::Start { user } => {
+            // 随机生成密码并开始游戏
+            let mut secret = [0; CODE_LENGTH];
+            for color in secret.iter_mut() {
+                *color = get_random_value(COLORS);
+            }
+            mastermind.games.insert(user, Game { secret, tries: 0 });
+            EngineEvent::Started { user }
+        }
+        EngineAction::Move { user, data } => {
+            let guess = Guess::decode(&mut &data[..]).expect("Unable to decode guess");
+            assert!(guess.is_valid(), "Invalid guess");
+            let game = mastermind
+                .games
+                .get_mut(&user)
+                .expect("There is no game with this user");
+            game.tries += 1;
+            let feedback = Feedback::new(&game.secret, &guess.0);
+            let result = if feedback.is_solved() {
+                EngineResult::Win
+            } else if game.tries == TRIES_LIMIT {
+                EngineResult::Lose
+            } else {
+                EngineResult::Continue
+            };
+            // 游戏结束后移除
+            if result != EngineResult::Continue {
+                mastermind.games.remove(&user);
+            }
+            EngineEvent::MoveMade {
+                user,
+                data: feedback.encode(),
+                result,
+            }
+        }
+    };
+
+    // 发送回复消息
+    msg::reply(reply, 0).expect("Error in sending a reply");
+}
+
+// 随机数种子
+static mut SEED: u8 = 0;
+
+// 获取随机值的函数
+pub fn get_random_value(range: u8) -> u8 {
+    let seed = unsafe { SEED };
+    unsafe { SEED = SEED.wrapping_add(1) };
+    let mut random_input: [u8; 32] = exec::program_id().into();
+    random_input[0] = random_input[0].wrapping_add(seed);
+    let (random, _) = exec::random(random_input).expect("Error in getting random number");
+    random[0] % range
+}
@@ -10,61 +10,176 @@ pub struct GameSessionMetadata;
 impl Metadata for GameSessionMetadata {
     type Init = In<GameSessionInit>; // 初始化输入类型
     type Handle = InOut<GameSessionAction, GameSessionEvent>; // 处理动作和事件类型
-    type State = Out<GameSessionState>; // 状态输出类型
+    type State = Out<GameSessionState>; // 状态输出类型，其他查询见 StateQuery
     type Reply = (); // 回复类型
     type Others = (); // 其他类型
     type Signal = (); // 信号类型
 }
 
+// 游戏类型ID
+pub type GameTypeId = u32;
+
+// Wordle 的游戏类型，注册表中始终存在
+pub const WORDLE_GAME_TYPE: GameTypeId = 0;
+
+// 单词包ID、默认单词包和游戏结束原因在 gword_core 中定义
+pub use gword_core::{GameOutcome, PackId, PackedFeedback, DEFAULT_PACK};
+use gword_core::{Phase, SessionState};
+
+// 与 Wordle 程序通信的消息和反馈直接使用 gword_io 中的类型
+pub use gword_io::{Action as WordleAction, BoardFeedback, Event as WordleEvent, HangmanStatus};
+
 // 游戏会话结构体
 #[derive(Default, Debug, Clone)]
 pub struct GameSession {
-    pub wordle_program_id: ActorId,              // Wordle程序ID
-    pub sessions: HashMap<ActorId, SessionInfo>, // 存储会话信息的哈希表
+    pub admin: ActorId,                               // 管理员，可以注册游戏程序
+    pub engines: HashMap<GameTypeId, ActorId>,        // 游戏程序注册表
+    pub sessions: HashMap<ActorId, SessionInfo>,      // 存储会话信息的哈希表
+    pub session_keys: HashMap<ActorId, SessionKey>,   // 会话密钥（代理账户 -> 授权信息）
+    pub stats: HashMap<ActorId, PlayerStats>,         // 玩家统计
+    pub analytics: GameAnalytics,                     // 全局游戏统计
+    pub config: GameSessionConfig,                    // 游戏配置
+    pub hint_revenue: u128,                           // 付费提示的收入，管理员可以提取
+    pub pending_replies: HashMap<MessageId, ActorId>, // 等待回复的请求（发送的消息ID -> 用户）
+    pub failed_messages: HashMap<MessageId, GameSessionEvent>, // 请求被拒绝、等待回复失败的消息
+    pub session_key_proposals: HashMap<(ActorId, ActorId), SessionKey>, // 等待代理账户接受的会话密钥（（用户, 代理账户） -> 授权信息）
+}
+
+impl GameSession {
+    // 获取 Wordle 程序ID
+    pub fn wordle_program_id(&self) -> ActorId {
+        self.engines
+            .get(&WORDLE_GAME_TYPE)
+            .copied()
+            .expect("Wordle program is not registered")
+    }
 }
 
 // 从游戏会话创建游戏会话状态
 impl From<&GameSession> for GameSessionState {
     fn from(game_session: &GameSession) -> Self {
         Self {
-            wordle_program_id: game_session.wordle_program_id,
+            wordle_program_id: game_session.wordle_program_id(),
+            admin: game_session.admin,
+            engines: game_session.engines.iter().map(|(k, v)| (*k, *v)).collect(),
+            config: game_session.config.clone(),
             game_sessions: game_session
                 .sessions
                 .iter()
                 .map(|(k, v)| (*k, v.clone()))
                 .collect(),
+            session_keys: game_session
+                .session_keys
+                .iter()
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
+            player_stats: game_session
+                .stats
+                .iter()
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
+            hint_revenue: game_session.hint_revenue,
+            session_key_proposals: game_session
+                .session_key_proposals
+                .iter()
+                .map(|(k, v)| (*k, v.clone()))
+                .collect(),
         }
     }
 }
 
+// 状态查询，空的查询返回完整的游戏会话状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum StateQuery {
+    Full,      // 完整的游戏会话状态，回复 GameSessionState
+    Analytics, // 全局游戏统计，回复 GameAnalytics
+}
+
 // 游戏会话状态，包含Wordle程序ID和游戏会话
 #[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
 pub struct GameSessionState {
     pub wordle_program_id: ActorId,                 // Wordle程序ID
+    pub admin: ActorId,                             // 管理员
+    pub engines: Vec<(GameTypeId, ActorId)>,        // 游戏程序注册表
+    pub config: GameSessionConfig,                  // 游戏配置
     pub game_sessions: Vec<(ActorId, SessionInfo)>, // 游戏会话
+    pub session_keys: Vec<(ActorId, SessionKey)>,   // 会话密钥
+    pub player_stats: Vec<(ActorId, PlayerStats)>,  // 玩家统计
+    pub hint_revenue: u128,                         // 尚未提取的提示收入
+    pub session_key_proposals: Vec<((ActorId, ActorId), SessionKey)>, // 等待接受的会话密钥
 }
 
 // 游戏会话初始化结构体，包含Wordle程序ID
 #[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
 pub struct GameSessionInit {
     pub wordle_program_id: ActorId, // Wordle程序ID
+    pub config: GameSessionConfig,  // 游戏配置
 }
 
 impl GameSessionInit {
-    // 校验Wordle程序ID是否有效
+    // 校验Wordle程序ID和游戏配置是否有效
     pub fn assert_valid(&self) {
         assert!(
             !self.wordle_program_id.is_zero(),
             "Invalid wordle_program_id"
         );
+        if let Some(move_timeout) = &self.config.move_timeout {
+            assert!(move_timeout.blocks > 0, "Invalid move_timeout");
+        }
+    }
+}
+
+// 游戏配置
+#[derive(Debug, Default, Clone, Encode, Decode, TypeInfo)]
+pub struct GameSessionConfig {
+    pub move_timeout: Option<MoveTimeout>, // 每步限时，None 表示不限时
+    pub hint_price: u128,                  // 提示的价格，0 表示不能付费购买
+    pub max_hints: u8,                     // 每局最多提示次数，0 表示禁用提示
+    pub hangman_max_wrong: u8,             // 猜字母游戏允许猜错的字母数，0 表示使用默认值
+}
+
+// 尝试次数限制
+pub const TRIES_LIMIT: u8 = 5;
+
+// 整局游戏的时限，200 区块（10 分钟）
+pub const GAME_TIMEOUT_BLOCKS: u32 = 200;
+
+// 猜字母游戏默认允许猜错的字母数
+pub const HANGMAN_MAX_WRONG: u8 = 6;
+
+impl GameSessionConfig {
+    // 猜字母游戏允许猜错的字母数
+    pub fn hangman_max_wrong(&self) -> u8 {
+        if self.hangman_max_wrong == 0 {
+            HANGMAN_MAX_WRONG
+        } else {
+            self.hangman_max_wrong
+        }
     }
 }
 
+// 每步限时配置
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub struct MoveTimeout {
+    pub blocks: u32,                 // 每步允许的区块数
+    pub penalty: MoveTimeoutPenalty, // 超时惩罚
+}
+
+// 超时惩罚
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum MoveTimeoutPenalty {
+    LoseTry,  // 失去一次尝试机会
+    LoseGame, // 直接判负
+}
+
 // 从初始化创建游戏会话
 impl From<GameSessionInit> for GameSession {
     fn from(game_session_init: GameSessionInit) -> Self {
         Self {
-            wordle_program_id: game_session_init.wordle_program_id,
+            engines: [(WORDLE_GAME_TYPE, game_session_init.wordle_program_id)]
+                .into_iter()
+                .collect(),
+            config: game_session_init.config,
             ..Default::default()
         }
     }
@@ -73,7 +188,7 @@ impl From<GameSessionInit> for GameSession {
 // 游戏会话动作枚举，定义游戏中的各种动作
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
 pub enum GameSessionAction {
-    StartGame, // 开始游戏
+    StartGame, // 开始游戏，从默认单词包中出题
     CheckWord {
         word: String, // 检查单词
     },
@@ -81,13 +196,168 @@ pub enum GameSessionAction {
         user: ActorId, // 检查游戏状态
         session_id: MessageId,
     },
+    // 以下为后续加入的动作，只能添加在末尾，以保持已部署客户端的编码
+    StartMatch {
+        words: u8, // 开始比赛：连续猜 words 个单词
+    },
+    StartMultiGame {
+        boards: u8,   // 开始多棋盘游戏：同时猜 2、4 或 8 个单词
+        pack: PackId, // 单词包，单词数不能少于棋盘数
+    },
+    StartAdversarial {
+        pack: PackId, // 开始对抗游戏：单词随每次猜测而变化，候选单词来自该单词包
+    },
+    StartChallenge {
+        challenge_id: u32, // 开始其他玩家创建的挑战
+        code: Option<u64>, // 公开挑战的邀请码
+    },
+    StartHangman, // 开始猜字母游戏
+    GuessLetter {
+        letter: u8, // 猜一个小写字母
+    },
+    StartEngineGame {
+        game_type: GameTypeId, // 开始注册表中其他游戏程序的游戏
+    },
+    MakeMove {
+        data: Vec<u8>, // 在其他游戏程序中行动，内容由游戏程序定义
+    },
+    CheckMoveStatus {
+        user: ActorId, // 检查每步限时
+        session_id: MessageId,
+        tries: u8, // 计时开始时的尝试次数
+    },
+    RequestHint {
+        payment: HintPayment, // 请求提示：公开一个尚未猜中的字母
+    },
+    GiveUp,  // 放弃当前游戏（计为失败）
+    Restart, // 放弃当前游戏并立即开始新游戏
+    // 注册游戏程序（仅管理员）
+    RegisterEngine {
+        game_type: GameTypeId,
+        program_id: ActorId,
+    },
+    // 移除游戏程序（仅管理员，Wordle 不能移除）
+    RemoveEngine {
+        game_type: GameTypeId,
+    },
+    // 为代理账户提出会话密钥，代理账户接受后可以代表用户执行指定的动作
+    CreateSessionKey {
+        key: ActorId,                     // 代理账户
+        duration: u32,                    // 有效期（区块数）
+        allowed_actions: Vec<ActionKind>, // 允许的动作
+    },
+    // 撤销会话密钥或尚未接受的提议
+    DeleteSessionKey {
+        key: ActorId, // 代理账户
+    },
+    // 会话密钥到期（仅由程序自身的延迟消息发送）
+    ExpireSessionKey {
+        owner: ActorId,
+        key: ActorId,
+        expires_at: u32,
+    },
+    NegotiateProtocol {
+        versions: Vec<ProtocolVersion>, // 协商协议版本：客户端支持的版本
+    },
+    StartGameWithPack {
+        pack: PackId, // 开始游戏，从选择的单词包中出题
+    },
+    Withdraw, // 提取付费提示的收入（仅管理员）
+    // 代理账户接受用户提出的会话密钥
+    AcceptSessionKey {
+        owner: ActorId, // 提出密钥的用户
+    },
+}
+
+// 会话密钥可授权的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum ActionKind {
+    StartGame,         // 开始游戏
+    StartMatch,        // 开始比赛
+    StartMultiGame,    // 开始多棋盘游戏
+    StartAdversarial,  // 开始对抗游戏
+    StartChallenge,    // 开始挑战
+    CheckWord,         // 检查单词
+    GiveUp,            // 放弃游戏
+    Restart,           // 重新开始
+    RequestHint,       // 请求提示
+    StartHangman,      // 开始猜字母游戏
+    GuessLetter,       // 猜字母
+    StartEngineGame,   // 开始其他游戏
+    MakeMove,          // 在其他游戏中行动
+    NegotiateProtocol, // 协商协议版本
+}
+
+// 提示的支付方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum HintPayment {
+    Try,   // 消耗一次尝试机会
+    Value, // 支付 hint_price
+}
+
+// 会话密钥，允许代理账户在有效期内代表用户执行动作
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub struct SessionKey {
+    pub owner: ActorId,                   // 授权的用户
+    pub expires_at: u32,                  // 过期区块高度
+    pub allowed_actions: Vec<ActionKind>, // 允许的动作
+}
+
+impl SessionKey {
+    // 判断密钥在给定区块高度是否允许执行该动作
+    pub fn allows(&self, block_height: u32, action: ActionKind) -> bool {
+        block_height < self.expires_at && self.allowed_actions.contains(&action)
+    }
+}
+
+// 游戏程序的通用协议：会话程序发送的动作
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub enum EngineAction {
+    Start { user: ActorId },               // 开始游戏
+    Move { user: ActorId, data: Vec<u8> }, // 玩家行动
 }
 
-// Wordle动作枚举
+// 游戏程序的通用协议：游戏程序回复的事件
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
-pub enum WordleAction {
-    StartGame { user: ActorId },               // 开始游戏
-    CheckWord { user: ActorId, word: String }, // 检查单词
+pub enum EngineEvent {
+    Started {
+        user: ActorId, // 游戏开始
+    },
+    MoveMade {
+        user: ActorId,
+        data: Vec<u8>,        // 行动的反馈，内容由游戏程序定义
+        result: EngineResult, // 行动后的游戏结果
+    },
+}
+
+impl EngineEvent {
+    // 获取用户ID
+    pub fn get_user(&self) -> &ActorId {
+        match self {
+            EngineEvent::Started { user } => user,
+            EngineEvent::MoveMade { user, .. } => user,
+        }
+    }
+}
+
+// 行动后的游戏结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum EngineResult {
+    Continue, // 继续游戏
+    Win,      // 胜利
+    Lose,     // 失败
+}
+
+// 从游戏程序事件转换为游戏会话事件
+impl From<&EngineEvent> for GameSessionEvent {
+    fn from(engine_event: &EngineEvent) -> Self {
+        match engine_event {
+            EngineEvent::Started { .. } => GameSessionEvent::StartSuccess,
+            EngineEvent::MoveMade { data, .. } => {
+                GameSessionEvent::MoveResult { data: data.clone() }
+            }
+        }
+    }
 }
 
 // 游戏会话事件枚举，定义游戏中可能发生的事件
@@ -99,6 +369,134 @@ pub enum GameSessionEvent {
         contained_in_word: Vec<u8>, // 包含在单词中
     },
     GameOver(GameStatus), // 游戏结束
+    // 以下为后续加入的事件，只能添加在末尾，以保持已部署客户端的解码
+    MoveResult {
+        data: Vec<u8>, // 其他游戏程序对行动的反馈
+    },
+    EngineRegistered {
+        game_type: GameTypeId, // 游戏程序已注册
+        program_id: ActorId,
+    },
+    EngineRemoved {
+        game_type: GameTypeId, // 游戏程序已移除
+    },
+    SessionKeyCreated {
+        key: ActorId,    // 代理账户
+        expires_at: u32, // 过期区块高度
+    },
+    SessionKeyDeleted {
+        key: ActorId, // 代理账户
+    },
+    GaveUp {
+        word: Option<String>, // 放弃游戏，若可能则公开单词
+    },
+    MoveTimedOut {
+        tries: u8, // 本步超时，已计入的尝试次数
+    },
+    HintRevealed {
+        position: u8, // 提示的位置
+        letter: u8,   // 该位置的字母
+    },
+    HangmanStarted {
+        word_length: u8, // 猜字母游戏开始，单词长度
+    },
+    LetterResult {
+        letter: u8,         // 猜测的字母
+        positions: Vec<u8>, // 字母在单词中的所有位置
+        wrong_guesses: u8,  // 已猜错的字母数
+    },
+    BoardsResult {
+        boards: Vec<BoardFeedback>, // 多棋盘游戏中每个未猜中棋盘的反馈
+    },
+    ChallengeStarted {
+        challenge_id: u32,    // 挑战开始
+        creator: ActorId,     // 挑战的创建者
+        commitment: [u8; 32], // 单词的承诺，可在公开后校验
+    },
+    ChallengeRevealed {
+        challenge_id: u32, // 挑战结束后公开单词和盐值
+        word: String,
+        salt: [u8; 32],
+    },
+    // 发送给挑战的创建者
+    ChallengeCompleted {
+        challenge_id: u32,
+        player: ActorId,    // 参加挑战的玩家
+        status: GameStatus, // 玩家的结果
+        tries: u8,          // 玩家的尝试次数
+    },
+    NextWordStarted {
+        word: u8, // 比赛中的第几个单词（从 1 开始）
+    },
+    MatchOver(MatchInfo), // 比赛结束
+    ProtocolSelected {
+        version: ProtocolVersion, // 协商后本会话使用的协议版本
+    },
+    CheckWordResultV2 {
+        feedback: PackedFeedback, // 协议 V2 的猜词结果，每个字母 2 位
+    },
+    StartFailed, // Wordle 程序拒绝开始游戏，例如挑战不存在
+    Withdrawn {
+        amount: u128, // 提取的收入，随回复一起发送
+    },
+    ActionFailed, // 游戏程序拒绝了玩家的请求，会话回到等待玩家行动
+    SessionKeyProposed {
+        key: ActorId,    // 等待接受的代理账户
+        expires_at: u32, // 过期区块高度
+    },
+}
+
+impl GameSessionEvent {
+    // 按会话选择的协议版本编码猜词结果
+    pub fn check_word_result(feedback: PackedFeedback, protocol: ProtocolVersion) -> Self {
+        match protocol {
+            ProtocolVersion::V1 => {
+                let (correct_positions, contained_in_word) = feedback.into();
+                GameSessionEvent::CheckWordResult {
+                    correct_positions,
+                    contained_in_word,
+                }
+            }
+            ProtocolVersion::V2 => GameSessionEvent::CheckWordResultV2 { feedback },
+        }
+    }
+
+    // 猜词结果的紧凑反馈，兼容两个协议版本
+    pub fn feedback(&self) -> Option<PackedFeedback> {
+        match self {
+            GameSessionEvent::CheckWordResult {
+                correct_positions,
+                contained_in_word,
+            } => Some(PackedFeedback::from(&(
+                correct_positions.clone(),
+                contained_in_word.clone(),
+            ))),
+            GameSessionEvent::CheckWordResultV2 { feedback } => Some(*feedback),
+            _ => None,
+        }
+    }
+}
+
+// 会话协议版本，决定猜词结果的编码
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub enum ProtocolVersion {
+    #[default]
+    V1, // 猜词结果为两个位置列表（CheckWordResult）
+    V2, // 猜词结果为紧凑反馈（CheckWordResultV2）
+}
+
+impl ProtocolVersion {
+    // 程序支持的所有版本
+    pub const SUPPORTED: [ProtocolVersion; 2] = [ProtocolVersion::V1, ProtocolVersion::V2];
+
+    // 选择客户端和程序都支持的最高版本
+    pub fn negotiate(versions: &[ProtocolVersion]) -> Option<Self> {
+        versions
+            .iter()
+            .copied()
+            .filter(|version| Self::SUPPORTED.contains(version))
+            .max()
+    }
 }
 
 // 游戏状态枚举，定义游戏可能的结果
@@ -108,54 +506,139 @@ pub enum GameStatus {
     Lose, // 失败
 }
 
-// Wordle事件枚举，定义Wordle游戏中可能发生的事件
-#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
-pub enum WordleEvent {
-    GameStarted {
-        user: ActorId, // 游戏开始
-    },
-    WordChecked {
-        user: ActorId, // 单词检查
-        correct_positions: Vec<u8>,
-        contained_in_word: Vec<u8>,
-    },
+// 玩家统计
+#[derive(Default, Debug, Clone, Encode, Decode, TypeInfo)]
+pub struct PlayerStats {
+    pub games_played: u32,               // 已结束的游戏数
+    pub wins: u32,                       // 胜利次数
+    pub losses: u32,                     // 失败次数（包含放弃）
+    pub gave_up: u32,                    // 放弃次数
+    pub packs: Vec<(PackId, PackStats)>, // 按单词包统计的经典模式结果
 }
 
-impl WordleEvent {
-    // 获取用户ID
-    pub fn get_user(&self) -> &ActorId {
-        match self {
-            WordleEvent::GameStarted { user } => user,
-            WordleEvent::WordChecked { user, .. } => user,
+impl PlayerStats {
+    // 记录一局游戏的结果，pack 为经典模式的单词包
+    pub fn record(&mut self, status: &GameStatus, gave_up: bool, pack: Option<PackId>) {
+        self.games_played += 1;
+        match status {
+            GameStatus::Win => self.wins += 1,
+            GameStatus::Lose => self.losses += 1,
+        }
+        if gave_up {
+            self.gave_up += 1;
+        }
+        if let Some(pack) = pack {
+            let pack_stats = match self.packs.iter_mut().find(|(id, _)| *id == pack) {
+                Some((_, pack_stats)) => pack_stats,
+                None => {
+                    self.packs.push((pack, PackStats::default()));
+                    &mut self.packs.last_mut().expect("The pack was just added").1
+                }
+            };
+            pack_stats.games_played += 1;
+            if matches!(status, GameStatus::Win) {
+                pack_stats.wins += 1;
+            }
+        }
+    }
+}
+
+// 单个单词包的统计
+#[derive(Default, Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PackStats {
+    pub games_played: u32, // 已结束的游戏数
+    pub wins: u32,         // 胜利次数
+}
+
+// 一局游戏的结果
+impl From<GameOutcome> for GameStatus {
+    fn from(outcome: GameOutcome) -> Self {
+        match outcome {
+            GameOutcome::Won => GameStatus::Win,
+            GameOutcome::LostByTries | GameOutcome::LostByTimeout | GameOutcome::Abandoned => {
+                GameStatus::Lose
+            }
         }
     }
+}
 
-    // 判断是否猜中
-    pub fn has_guessed(&self) -> bool {
-        match self {
-            WordleEvent::GameStarted { .. } => unimplemented!(),
-            WordleEvent::WordChecked {
-                correct_positions, ..
-            } => correct_positions == &vec![0, 1, 2, 3, 4],
+// 统计周期的长度（区块数，约一天）
+pub const ANALYTICS_EPOCH_BLOCKS: u32 = 28_800;
+// 保留的统计周期数
+pub const ANALYTICS_EPOCHS_KEPT: usize = 30;
+
+// 全局游戏统计，在每次状态变化时增量更新
+#[derive(Default, Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct GameAnalytics {
+    pub totals: AnalyticsCounters,             // 累计统计
+    pub epochs: Vec<(u32, AnalyticsCounters)>, // 按周期统计（周期编号 -> 统计），按周期升序
+}
+
+impl GameAnalytics {
+    // 周期编号
+    pub fn epoch(block_height: u32) -> u32 {
+        block_height / ANALYTICS_EPOCH_BLOCKS
+    }
+
+    // 记录一局游戏开始
+    pub fn record_start(&mut self, block_height: u32) {
+        self.totals.started += 1;
+        self.epoch_mut(block_height).started += 1;
+    }
+
+    // 记录一局游戏结束
+    pub fn record_finish(&mut self, block_height: u32, outcome: GameOutcome, tries: u8) {
+        self.totals.record_finish(outcome, tries);
+        self.epoch_mut(block_height).record_finish(outcome, tries);
+    }
+
+    // 进行中的游戏数
+    pub fn active_games(&self) -> u32 {
+        self.totals.started.saturating_sub(self.totals.finished)
+    }
+
+    // 获取当前周期的统计，必要时新建并丢弃最旧的周期
+    fn epoch_mut(&mut self, block_height: u32) -> &mut AnalyticsCounters {
+        let epoch = Self::epoch(block_height);
+        if !matches!(self.epochs.last(), Some((last, _)) if *last == epoch) {
+            self.epochs.push((epoch, AnalyticsCounters::default()));
+            if self.epochs.len() > ANALYTICS_EPOCHS_KEPT {
+                self.epochs.remove(0);
+            }
         }
+        &mut self.epochs.last_mut().expect("The epoch was just added").1
     }
 }
 
-// 从Wordle事件转换为游戏会话事件
-impl From<&WordleEvent> for GameSessionEvent {
-    fn from(wordle_event: &WordleEvent) -> Self {
-        match wordle_event {
-            WordleEvent::GameStarted { .. } => GameSessionEvent::StartSuccess,
-            WordleEvent::WordChecked {
-                correct_positions,
-                contained_in_word,
-                ..
-            } => GameSessionEvent::CheckWordResult {
-                correct_positions: correct_positions.clone(),
-                contained_in_word: contained_in_word.clone(),
-            },
+// 游戏统计计数
+#[derive(Default, Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct AnalyticsCounters {
+    pub started: u32,         // 开始的游戏数
+    pub finished: u32,        // 结束的游戏数
+    pub won: u32,             // 胜利次数
+    pub lost_by_tries: u32,   // 用尽尝试次数的失败次数
+    pub lost_by_timeout: u32, // 超时的失败次数
+    pub abandoned: u32,       // 放弃次数
+    pub total_tries: u64,     // 已结束游戏的尝试次数之和
+}
+
+impl AnalyticsCounters {
+    // 记录一局游戏结束
+    fn record_finish(&mut self, outcome: GameOutcome, tries: u8) {
+        self.finished += 1;
+        self.total_tries += tries as u64;
+        match outcome {
+            GameOutcome::Won => self.won += 1,
+            GameOutcome::LostByTries => self.lost_by_tries += 1,
+            GameOutcome::LostByTimeout => self.lost_by_timeout += 1,
+            GameOutcome::Abandoned => self.abandoned += 1,
         }
     }
+
+    // 已结束游戏的平均尝试次数（乘以 100），没有游戏时为 None
+    pub fn average_tries_x100(&self) -> Option<u64> {
+        (self.finished > 0).then(|| self.total_tries * 100 / self.finished as u64)
+    }
 }
 
 // 会话状态枚举，定义会话可能的状态
@@ -163,11 +646,17 @@ impl From<&WordleEvent> for GameSessionEvent {
 pub enum SessionStatus {
     #[default]
     Init, // 初始化
-    WaitUserInput,              // 等待用户输入
-    WaitWordleStartReply,       // 等待Wordle开始回复
-    WaitWordleCheckWordReply,   // 等待Wordle检查单词回复
-    ReplyReceived(WordleEvent), // 收到回复
-    GameOver(GameStatus),       // 游戏结束
+    WaitUserInput,                    // 等待用户输入
+    WaitWordleStartReply,             // 等待Wordle开始回复
+    WaitWordleCheckWordReply,         // 等待Wordle检查单词回复
+    WaitWordleRevealReply,            // 等待Wordle公开单词回复
+    WaitWordleHintReply,              // 等待Wordle提示回复
+    WaitWordleNextWordReply,          // 比赛中等待Wordle下一个单词的回复
+    WaitEngineStartReply,             // 等待游戏程序开始回复
+    WaitEngineMoveReply,              // 等待游戏程序行动回复
+    ReplyReceived(WordleEvent),       // 收到回复
+    EngineReplyReceived(EngineEvent), // 收到游戏程序回复
+    GameOver(GameStatus),             // 游戏结束
 }
 
 // 会话信息结构体，包含会话的详细信息
@@ -176,16 +665,240 @@ pub struct SessionInfo {
     pub session_id: MessageId,            // 会话ID
     pub original_msg_id: MessageId,       // 原始消息ID
     pub send_to_wordle_msg_id: MessageId, // 发送到Wordle的消息ID
+    pub game_type: GameTypeId,            // 游戏类型
+    pub mode: WordleMode,                 // Wordle 的游戏模式
+    pub pack: PackId,                     // 经典模式的单词包
     pub tries: u8,                        // 尝试次数
     pub session_status: SessionStatus,    // 会话状态
+    pub history: Vec<HistoryEntry>,       // 本局的猜词和提示记录
+    pub match_info: Option<MatchInfo>,    // 比赛信息，None 表示单局游戏
+    pub challenge: Option<ChallengeInfo>, // 挑战信息，None 表示随机单词
+    pub protocol: ProtocolVersion,        // 协议版本，跨局保留
+    pub send_to_engine_msg_id: MessageId, // 发送到其他游戏程序的消息ID
+    pub move_deadline: u32,               // 最近一次每步计时的截止区块，更早触发的计时器已过期
 }
 
 impl SessionInfo {
+    // 会话在 gword_core 状态机中的状态
+    pub fn core_state(&self) -> SessionState {
+        let phase = match &self.session_status {
+            SessionStatus::Init => Phase::Idle,
+            SessionStatus::WaitWordleStartReply
+            | SessionStatus::WaitWordleNextWordReply
+            | SessionStatus::WaitEngineStartReply
+            | SessionStatus::EngineReplyReceived(EngineEvent::Started { .. }) => Phase::Starting,
+            SessionStatus::WaitUserInput => Phase::Playing,
+            SessionStatus::WaitWordleHintReply => Phase::Hinting,
+            SessionStatus::WaitWordleCheckWordReply
+            | SessionStatus::WaitEngineMoveReply
+            | SessionStatus::EngineReplyReceived(EngineEvent::MoveMade { .. }) => Phase::Checking,
+            SessionStatus::WaitWordleRevealReply => Phase::Revealing,
+            SessionStatus::ReplyReceived(wordle_event) => match wordle_event {
+                WordleEvent::GameStarted { .. }
+                | WordleEvent::HangmanStarted { .. }
+                | WordleEvent::ChallengeStarted { .. } => Phase::Starting,
+                WordleEvent::WordChecked { .. }
+                | WordleEvent::LetterChecked { .. }
+                | WordleEvent::BoardsChecked { .. } => Phase::Checking,
+                WordleEvent::WordRevealed { .. } | WordleEvent::ChallengeRevealed { .. } => {
+                    Phase::Revealing
+                }
+                WordleEvent::LetterRevealed { .. } => Phase::Hinting,
+                WordleEvent::ResultRecorded { .. }
+                | WordleEvent::ChallengeCreated { .. }
+                | WordleEvent::ChallengeOpened { .. } => Phase::Over,
+            },
+            SessionStatus::GameOver(..) => Phase::Over,
+        };
+        SessionState {
+            phase,
+            tries: self.tries,
+            tries_limit: self.tries_limit(),
+        }
+    }
+
+    // 本局的尝试次数限制，其他游戏程序自行判定胜负
+    pub fn tries_limit(&self) -> Option<u8> {
+        if self.game_type == WORDLE_GAME_TYPE {
+            self.mode.tries_limit()
+        } else {
+            None
+        }
+    }
+
     // 判断是否处于等待回复状态
     pub fn is_wait_reply_status(&self) -> bool {
         matches!(
             self.session_status,
-            SessionStatus::WaitWordleCheckWordReply | SessionStatus::WaitWordleStartReply
+            SessionStatus::WaitWordleCheckWordReply
+                | SessionStatus::WaitWordleStartReply
+                | SessionStatus::WaitWordleRevealReply
+                | SessionStatus::WaitWordleHintReply
+                | SessionStatus::WaitEngineStartReply
+                | SessionStatus::WaitEngineMoveReply
         )
     }
+
+    // 已使用的提示次数
+    pub fn hints_used(&self) -> u8 {
+        self.history
+            .iter()
+            .filter(|entry| matches!(entry, HistoryEntry::Hint { .. }))
+            .count() as u8
+    }
+
+    // 已知字母的位置：猜中的位置和提示过的位置
+    pub fn known_positions(&self) -> Vec<u8> {
+        let mut positions: Vec<u8> = self
+            .history
+            .iter()
+            .flat_map(|entry| match entry {
+                HistoryEntry::Guess {
+                    correct_positions, ..
+                } => correct_positions.clone(),
+                HistoryEntry::Hint { position, .. } => vec![*position],
+                HistoryEntry::Letter { positions, .. } => positions.clone(),
+                HistoryEntry::Boards { .. } | HistoryEntry::Move { .. } => vec![],
+            })
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    // 多棋盘游戏中已猜中的棋盘
+    pub fn solved_boards(&self) -> Vec<u8> {
+        self.history
+            .iter()
+            .flat_map(|entry| match entry {
+                HistoryEntry::Boards { boards, .. } => boards
+                    .iter()
+                    .filter(|feedback| feedback.is_solved())
+                    .map(|feedback| feedback.board)
+                    .collect(),
+                _ => vec![],
+            })
+            .collect()
+    }
+}
+
+// Wordle 的游戏模式
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum WordleMode {
+    #[default]
+    Classic, // 猜整个单词
+    Hangman, // 猜字母
+    MultiBoard {
+        boards: u8, // 同时猜多个单词
+    },
+    Adversarial, // 单词不固定，每次猜测都给出最不利的反馈
+    Challenge {
+        challenge_id: u32, // 其他玩家创建的挑战
+        code: Option<u64>,
+    },
+}
+
+impl WordleMode {
+    // 开始该模式游戏的 Wordle 动作
+    pub fn start_action(
+        &self,
+        user: ActorId,
+        pack: PackId,
+        config: &GameSessionConfig,
+    ) -> WordleAction {
+        match self {
+            WordleMode::Classic => WordleAction::StartGame { user, pack },
+            WordleMode::Hangman => WordleAction::StartHangman {
+                user,
+                max_wrong: config.hangman_max_wrong(),
+            },
+            WordleMode::MultiBoard { boards } => WordleAction::StartMultiGame {
+                user,
+                boards: *boards,
+                pack,
+            },
+            WordleMode::Adversarial => WordleAction::StartAdversarial { user, pack },
+            WordleMode::Challenge { challenge_id, code } => WordleAction::StartChallenge {
+                user,
+                challenge_id: *challenge_id,
+                code: *code,
+            },
+        }
+    }
+
+    // Wordle 程序不保存该模式的猜测，等待回复时重新发送的猜测不会被执行两次
+    pub fn is_stateless(&self) -> bool {
+        matches!(self, WordleMode::Classic | WordleMode::Challenge { .. })
+    }
+
+    // 该模式的尝试次数限制，多棋盘模式每多一个棋盘多一次机会；猜字母游戏由 Wordle 程序按猜错的字母数判定胜负
+    pub fn tries_limit(&self) -> Option<u8> {
+        match self {
+            WordleMode::MultiBoard { boards } => Some(TRIES_LIMIT + boards),
+            WordleMode::Hangman => None,
+            WordleMode::Classic | WordleMode::Adversarial | WordleMode::Challenge { .. } => {
+                Some(TRIES_LIMIT)
+            }
+        }
+    }
+}
+
+// 正在进行的挑战
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct ChallengeInfo {
+    pub challenge_id: u32,    // 挑战ID
+    pub creator: ActorId,     // 挑战的创建者，结束时会收到结果
+    pub commitment: [u8; 32], // 单词的承诺
+}
+
+// 比赛信息，汇总比赛中每一局的结果
+#[derive(Default, Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct MatchInfo {
+    pub words: u8,        // 比赛的单词数
+    pub played: u8,       // 已结束的局数
+    pub wins: u8,         // 胜利的局数
+    pub total_tries: u32, // 所有局的尝试次数之和
+}
+
+impl MatchInfo {
+    // 记录一局的结果
+    pub fn record(&mut self, status: &GameStatus, tries: u8) {
+        self.played += 1;
+        self.total_tries += tries as u32;
+        if matches!(status, GameStatus::Win) {
+            self.wins += 1;
+        }
+    }
+
+    // 判断比赛是否结束
+    pub fn is_over(&self) -> bool {
+        self.played >= self.words
+    }
+}
+
+// 会话历史记录
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub enum HistoryEntry {
+    Guess {
+        word: String,               // 猜测的单词
+        correct_positions: Vec<u8>, // 正确位置
+        contained_in_word: Vec<u8>, // 包含在单词中
+    },
+    Hint {
+        position: u8, // 提示的位置
+        letter: u8,   // 该位置的字母
+        payment: HintPayment,
+    },
+    Letter {
+        letter: u8,         // 猜字母游戏中猜测的字母
+        positions: Vec<u8>, // 字母在单词中的所有位置
+    },
+    Boards {
+        word: String,               // 多棋盘游戏中猜测的单词
+        boards: Vec<BoardFeedback>, // 每个未猜中棋盘的反馈
+    },
+    Move {
+        data: Vec<u8>,     // 其他游戏中的行动
+        feedback: Vec<u8>, // 游戏程序的反馈
+    },
 }
@@ -0,0 +1,47 @@
+use gsession_io::*;
+use gstd::{ActorId, MessageId};
+use parity_scale_codec::{Decode, Encode};
+
+// 旧版客户端编码的动作仍能按原来的含义解码
+#[test]
+fn baseline_actions_decode() {
+    let action = GameSessionAction::decode(&mut &[0][..]).expect("StartGame");
+    assert!(matches!(action, GameSessionAction::StartGame));
+
+    let mut bytes = vec![1, 20];
+    bytes.extend_from_slice(b"human");
+    let action = GameSessionAction::decode(&mut &bytes[..]).expect("CheckWord");
+    assert!(matches!(action, GameSessionAction::CheckWord { word } if word == "human"));
+
+    let mut bytes = vec![2];
+    bytes.extend_from_slice(&[1; 32]);
+    bytes.extend_from_slice(&[2; 32]);
+    let action = GameSessionAction::decode(&mut &bytes[..]).expect("CheckGameStatus");
+    assert!(matches!(
+        action,
+        GameSessionAction::CheckGameStatus { user, session_id }
+            if user == ActorId::from([1; 32]) && session_id == MessageId::from([2; 32])
+    ));
+}
+
+// 原有的事件保持旧版客户端能解码的编码
+#[test]
+fn baseline_events_encode() {
+    assert_eq!(GameSessionEvent::StartSuccess.encode(), vec![0]);
+    assert_eq!(
+        GameSessionEvent::CheckWordResult {
+            correct_positions: vec![0, 4],
+            contained_in_word: vec![2],
+        }
+        .encode(),
+        vec![1, 8, 0, 4, 4, 2]
+    );
+    assert_eq!(
+        GameSessionEvent::GameOver(GameStatus::Win).encode(),
+        vec![2, 0]
+    );
+    assert_eq!(
+        GameSessionEvent::GameOver(GameStatus::Lose).encode(),
+        vec![2, 1]
+    );
+}
@@ -1,9 +1,12 @@
 #![no_std]
 use gsession_io::*;
-use gstd::*;
+use gstd::{collections::HashMap, *};
+use gword_core::{is_valid_word, transition, Effect, Input, MoveResult, Phase};
 
-// 尝试次数限制
-const TRIES_LIMIT: u8 = 5;
+// 会话密钥最长有效期（区块数，约一天）
+const SESSION_KEY_MAX_DURATION: u32 = 28_800;
+// 每场比赛最多的单词数
+const MATCH_WORDS_LIMIT: u8 = 10;
 
 // 静态变量存储游戏会话状态
 static mut GAME_SESSION_STATE: Option<GameSession> = None;
@@ -32,8 +35,10 @@ extern "C" fn init() {
     let game_session_init: GameSessionInit =
         msg::load().expect("Unable to decode GameSessionInit: init");
     game_session_init.assert_valid();
+    let mut game_session: GameSession = game_session_init.into();
+    game_session.admin = msg::source();
     unsafe {
-        GAME_SESSION_STATE = Some(game_session_init.into());
+        GAME_SESSION_STATE = Some(game_session);
     };
 }
 
@@ -42,141 +47,1278 @@ extern "C" fn handle() {
     let game_session_action: GameSessionAction =
         msg::load().expect("Unable to decode GameSessionAction: handle");
     let game_session = get_game_session_mut();
+    // 游戏程序拒绝了本消息等待的请求：回复失败并退还附带的金额
+    if let Some(event) = game_session.failed_messages.remove(&msg::id()) {
+        msg::reply(event, msg::value()).expect("Failed to send reply: handle");
+        return;
+    }
     match game_session_action {
-        // Action 1: 开始游戏
-        GameSessionAction::StartGame => {
-            let user = msg::source();
-            // 检查用户是否已有游戏会话
+        // Action 1: 开始游戏，不指定单词包时使用默认单词包
+        GameSessionAction::StartGame => start_wordle_game(
+            game_session,
+            ActionKind::StartGame,
+            WordleMode::Classic,
+            DEFAULT_PACK,
+        ),
+        GameSessionAction::StartGameWithPack { pack } => start_wordle_game(
+            game_session,
+            ActionKind::StartGame,
+            WordleMode::Classic,
+            pack,
+        ),
+        // Action 2: 开始比赛，连续猜多个单词
+        GameSessionAction::StartMatch { words } => {
+            let user = resolve_user(game_session, ActionKind::StartMatch);
+            assert!(
+                words > 0 && words <= MATCH_WORDS_LIMIT,
+                "Invalid number of words: StartMatch"
+            );
+            let match_info = MatchInfo {
+                words,
+                ..Default::default()
+            };
+            let request = StartRequest::Wordle {
+                mode: WordleMode::Classic,
+                pack: DEFAULT_PACK,
+            };
+            start_session(
+                game_session,
+                user,
+                ActionKind::StartMatch,
+                request,
+                Some(match_info),
+            );
+        }
+        // Action 3: 开始注册表中其他游戏程序的游戏
+        GameSessionAction::StartEngineGame { game_type } => {
+            let user = resolve_user(game_session, ActionKind::StartEngineGame);
+            // Wordle 使用 StartGame 开始
+            assert!(
+                game_type != WORDLE_GAME_TYPE,
+                "Invalid game type: StartEngineGame"
+            );
+            let program_id = *game_session
+                .engines
+                .get(&game_type)
+                .expect("Unknown game type: StartEngineGame");
+            let request = StartRequest::Engine {
+                game_type,
+                program_id,
+            };
+            start_session(
+                game_session,
+                user,
+                ActionKind::StartEngineGame,
+                request,
+                None,
+            );
+        }
+        // Action 4: 开始多棋盘游戏，每次猜测同时用于所有未猜中的棋盘
+        GameSessionAction::StartMultiGame { boards, pack } => {
+            assert!(
+                matches!(boards, 2 | 4 | 8),
+                "Invalid number of boards: StartMultiGame"
+            );
+            start_wordle_game(
+                game_session,
+                ActionKind::StartMultiGame,
+                WordleMode::MultiBoard { boards },
+                pack,
+            );
+        }
+        // Action 5: 开始对抗游戏，单词随每次猜测而变化
+        GameSessionAction::StartAdversarial { pack } => start_wordle_game(
+            game_session,
+            ActionKind::StartAdversarial,
+            WordleMode::Adversarial,
+            pack,
+        ),
+        // Action 6: 开始其他玩家创建的挑战
+        GameSessionAction::StartChallenge { challenge_id, code } => start_wordle_game(
+            game_session,
+            ActionKind::StartChallenge,
+            WordleMode::Challenge { challenge_id, code },
+            DEFAULT_PACK,
+        ),
+        // Action 7: 检查单词
+        GameSessionAction::CheckWord { word } => {
+            let user = resolve_user(game_session, ActionKind::CheckWord);
             let session_info = game_session.sessions.entry(user).or_default();
             match &session_info.session_status {
-                SessionStatus::ReplyReceived(wordle_event) => {
-                    // 通知用户游戏已成功开始
-                    msg::reply::<GameSessionEvent>(wordle_event.into(), 0)
-                        .expect("Failed to send reply: StartGame");
-                    session_info.session_status = SessionStatus::WaitUserInput;
+                SessionStatus::ReplyReceived(wordle_event) if is_woken(session_info) => {
+                    // 只接受猜词的回复，其他回复属于等待中的动作
+                    let (entry, event, solved) =
+                        check_word_reply(wordle_event, word, session_info.protocol)
+                            .expect("Waiting for a reply: CheckWord");
+                    // 记录本次猜词
+                    session_info.history.push(entry);
+                    // 猜对时胜利，用尽所有尝试时失败
+                    advance(
+                        game_session,
+                        user,
+                        Input::Checked { solved },
+                        None,
+                        Some(event),
+                    );
                 }
-                SessionStatus::Init
-                | SessionStatus::GameOver(..)
-                | SessionStatus::WaitWordleStartReply => {
-                    // 发送 "StartGame" 消息给 Wordle 程序
-                    let send_to_wordle_msg_id = msg::send(
-                        game_session.wordle_program_id,
-                        WordleAction::StartGame { user },
-                        0,
-                    )
-                    .expect("Failed to send message: StartGame");
-
-                    session_info.session_id = msg::id();
-                    session_info.original_msg_id = msg::id();
-                    session_info.send_to_wordle_msg_id = send_to_wordle_msg_id;
-                    session_info.tries = 0;
-                    session_info.session_status = SessionStatus::WaitWordleStartReply;
-                    // 发送延迟消息以监控游戏进度，延迟为 200 区块（10 分钟）
-                    msg::send_delayed(
-                        exec::program_id(),
-                        GameSessionAction::CheckGameStatus {
-                            user,
-                            session_id: msg::id(),
-                        },
-                        0,
-                        200,
-                    )
-                    .expect("Failed to send delayed message: StartGame");
-                    // 等待响应
+                // 确保游戏存在且在正确状态
+                SessionStatus::WaitUserInput | SessionStatus::WaitWordleCheckWordReply => {
+                    assert!(
+                        session_info.game_type == WORDLE_GAME_TYPE
+                            && session_info.mode != WordleMode::Hangman,
+                        "Not a word guessing game: CheckWord"
+                    );
+                    // 验证单词长度为五且为小写
+                    assert!(is_valid_word(&word), "Invalid word: CheckWord");
+                    // 发送 "CheckWord" 消息给 Wordle 程序
+                    let request = Request::Wordle(WordleAction::CheckWord { user, word });
+                    let resendable = session_info.mode.is_stateless();
+                    advance(
+                        game_session,
+                        user,
+                        Input::Move { resendable },
+                        Some(request),
+                        None,
+                    );
+                    // 等待回复
                     exec::wait();
                 }
-                SessionStatus::WaitUserInput | SessionStatus::WaitWordleCheckWordReply => {
-                    panic!("User is already in the game: StartGame");
+                SessionStatus::ReplyReceived(..) => {
+                    panic!("Waiting for a reply: CheckWord");
+                }
+                SessionStatus::Init
+                | SessionStatus::WaitWordleStartReply
+                | SessionStatus::WaitWordleRevealReply
+                | SessionStatus::WaitWordleHintReply
+                | SessionStatus::WaitWordleNextWordReply
+                | SessionStatus::WaitEngineStartReply
+                | SessionStatus::WaitEngineMoveReply
+                | SessionStatus::EngineReplyReceived(..)
+                | SessionStatus::GameOver(..) => {
+                    panic!("User is not in the game: CheckWord");
                 }
             }
         }
-        // Action 2: 检查单词
-        GameSessionAction::CheckWord { word } => {
-            let user = msg::source();
+        // Action 8: 开始猜字母游戏
+        GameSessionAction::StartHangman => start_wordle_game(
+            game_session,
+            ActionKind::StartHangman,
+            WordleMode::Hangman,
+            DEFAULT_PACK,
+        ),
+        // Action 9: 猜字母
+        GameSessionAction::GuessLetter { letter } => {
+            let user = resolve_user(game_session, ActionKind::GuessLetter);
             let session_info = game_session.sessions.entry(user).or_default();
             match &session_info.session_status {
-                SessionStatus::ReplyReceived(wordle_event) => {
-                    // 增加尝试次数
-                    session_info.tries += 1;
-                    // 检查单词是否猜对
-                    if wordle_event.has_guessed() {
-                        // 若猜对，切换为游戏结束状态（胜利）
-                        session_info.session_status = SessionStatus::GameOver(GameStatus::Win);
-                        msg::reply(GameSessionEvent::GameOver(GameStatus::Win), 0)
-                            .expect("Failed to send reply: CheckWord");
-                    } else if session_info.tries == TRIES_LIMIT {
-                        // 若用尽所有尝试，切换为游戏结束状态（失败）
-                        session_info.session_status = SessionStatus::GameOver(GameStatus::Lose);
-                        msg::reply(GameSessionEvent::GameOver(GameStatus::Lose), 0)
-                            .expect("Failed to send reply: CheckWord");
-                    } else {
-                        msg::reply::<GameSessionEvent>(wordle_event.into(), 0)
-                            .expect("Failed to send reply: CheckWord");
-                        session_info.session_status = SessionStatus::WaitUserInput;
-                    }
+                SessionStatus::ReplyReceived(WordleEvent::LetterChecked {
+                    positions,
+                    wrong_guesses,
+                    status,
+                    ..
+                }) if is_woken(session_info) => {
+                    let event = GameSessionEvent::LetterResult {
+                        letter,
+                        positions: positions.clone(),
+                        wrong_guesses: *wrong_guesses,
+                    };
+                    // 由 Wordle 程序判定胜负
+                    let result = match status {
+                        HangmanStatus::InProgress => MoveResult::Continue,
+                        HangmanStatus::Won => MoveResult::Won,
+                        HangmanStatus::Lost => MoveResult::Lost,
+                    };
+                    session_info.history.push(HistoryEntry::Letter {
+                        letter,
+                        positions: positions.clone(),
+                    });
+                    advance(
+                        game_session,
+                        user,
+                        Input::Judged { result },
+                        None,
+                        Some(event),
+                    );
                 }
-                // 确保游戏存在且在正确状态
+                // 猜字母与猜单词共用等待回复的状态，等待时由状态机拒绝重新发送
                 SessionStatus::WaitUserInput | SessionStatus::WaitWordleCheckWordReply => {
-                    // 验证单词长度为五且为小写
                     assert!(
-                        word.len() == 5 && word.chars().all(|c| c.is_lowercase()),
-                        "Invalid word: CheckWord"
+                        session_info.game_type == WORDLE_GAME_TYPE
+                            && session_info.mode == WordleMode::Hangman,
+                        "Not a hangman game: GuessLetter"
+                    );
+                    assert!(letter.is_ascii_lowercase(), "Invalid letter: GuessLetter");
+                    let request = Request::Wordle(WordleAction::GuessLetter { user, letter });
+                    let resendable = session_info.mode.is_stateless();
+                    advance(
+                        game_session,
+                        user,
+                        Input::Move { resendable },
+                        Some(request),
+                        None,
                     );
-                    // 发送 "CheckWord" 消息给 Wordle 程序
-                    let send_to_wordle_msg_id = msg::send(
-                        game_session.wordle_program_id,
-                        WordleAction::CheckWord { user, word },
-                        0,
-                    )
-                    .expect("Failed to send message: CheckWord");
-                    session_info.original_msg_id = msg::id();
-                    session_info.send_to_wordle_msg_id = send_to_wordle_msg_id;
-                    session_info.session_status = SessionStatus::WaitWordleCheckWordReply;
-                    // 等待回复
                     exec::wait();
                 }
+                SessionStatus::ReplyReceived(..)
+                | SessionStatus::EngineReplyReceived(..)
+                | SessionStatus::WaitWordleRevealReply
+                | SessionStatus::WaitWordleHintReply
+                | SessionStatus::WaitWordleNextWordReply
+                | SessionStatus::WaitEngineMoveReply => {
+                    panic!("Waiting for a reply: GuessLetter");
+                }
                 SessionStatus::Init
                 | SessionStatus::WaitWordleStartReply
+                | SessionStatus::WaitEngineStartReply
                 | SessionStatus::GameOver(..) => {
-                    panic!("User is not in the game: CheckWord");
+                    panic!("User is not in the game: GuessLetter");
+                }
+            }
+        }
+        // Action 10: 在其他游戏程序中行动
+        GameSessionAction::MakeMove { data } => {
+            let user = resolve_user(game_session, ActionKind::MakeMove);
+            let session_info = game_session.sessions.entry(user).or_default();
+            match &session_info.session_status {
+                SessionStatus::EngineReplyReceived(
+                    engine_event @ EngineEvent::MoveMade {
+                        data: feedback,
+                        result,
+                        ..
+                    },
+                ) if is_woken(session_info) => {
+                    let event: GameSessionEvent = engine_event.into();
+                    // 由游戏程序判定胜负
+                    let result = match result {
+                        EngineResult::Continue => MoveResult::Continue,
+                        EngineResult::Win => MoveResult::Won,
+                        EngineResult::Lose => MoveResult::Lost,
+                    };
+                    session_info.history.push(HistoryEntry::Move {
+                        data,
+                        feedback: feedback.clone(),
+                    });
+                    advance(
+                        game_session,
+                        user,
+                        Input::Judged { result },
+                        None,
+                        Some(event),
+                    );
+                }
+                SessionStatus::WaitUserInput => {
+                    let engine_program_id = *game_session
+                        .engines
+                        .get(&session_info.game_type)
+                        .filter(|_| session_info.game_type != WORDLE_GAME_TYPE)
+                        .expect("Not an engine game: MakeMove");
+                    let request =
+                        Request::Engine(engine_program_id, EngineAction::Move { user, data });
+                    let input = Input::Move { resendable: false };
+                    advance(game_session, user, input, Some(request), None);
+                    exec::wait();
+                }
+                SessionStatus::ReplyReceived(..)
+                | SessionStatus::EngineReplyReceived(..)
+                | SessionStatus::WaitWordleCheckWordReply
+                | SessionStatus::WaitWordleRevealReply
+                | SessionStatus::WaitWordleHintReply
+                | SessionStatus::WaitWordleNextWordReply
+                | SessionStatus::WaitEngineMoveReply => {
+                    panic!("Waiting for a reply: MakeMove");
+                }
+                SessionStatus::Init
+                | SessionStatus::WaitWordleStartReply
+                | SessionStatus::WaitEngineStartReply
+                | SessionStatus::GameOver(..) => {
+                    panic!("User is not in the game: MakeMove");
                 }
             }
         }
-        // Action 3: 检查游戏状态
+        // Action 11: 检查游戏状态
         GameSessionAction::CheckGameStatus { user, session_id } => {
+            // 没有游戏或游戏已结束时由状态机忽略
+            if msg::source() == exec::program_id()
+                && game_session
+                    .sessions
+                    .get(&user)
+                    .is_some_and(|session_info| session_id == session_info.session_id)
+            {
+                advance(game_session, user, Input::GameTimeout, None, None);
+            }
+        }
+        // Action 12: 检查每步限时
+        GameSessionAction::CheckMoveStatus {
+            user,
+            session_id,
+            tries,
+        } => {
             if msg::source() == exec::program_id() {
-                if let Some(session_info) = game_session.sessions.get_mut(&user) {
+                if let (Some(move_timeout), Some(session_info)) = (
+                    game_session.config.move_timeout.as_ref(),
+                    game_session.sessions.get(&user),
+                ) {
+                    // 忽略过期的计时器：会话已变更、玩家已猜词、之后重新计时或不在等待输入状态
                     if session_id == session_info.session_id
-                        && !matches!(session_info.session_status, SessionStatus::GameOver(..))
+                        && tries == session_info.tries
+                        && exec::block_height() >= session_info.move_deadline
+                        && matches!(session_info.session_status, SessionStatus::WaitUserInput)
                     {
-                        session_info.session_status = SessionStatus::GameOver(GameStatus::Lose);
-                        msg::send(user, GameSessionEvent::GameOver(GameStatus::Lose), 0)
-                            .expect("Failed to send reply: CheckGameStatus");
+                        let lose_game = move_timeout.penalty == MoveTimeoutPenalty::LoseGame;
+                        advance(
+                            game_session,
+                            user,
+                            Input::MoveTimeout { lose_game },
+                            None,
+                            None,
+                        );
+                    }
+                }
+            }
+        }
+        // Action 13: 请求提示
+        GameSessionAction::RequestHint { payment } => {
+            let user = resolve_user(game_session, ActionKind::RequestHint);
+            let config = &game_session.config;
+            let session_info = game_session.sessions.entry(user).or_default();
+            let paid_with_try = payment == HintPayment::Try;
+            match &session_info.session_status {
+                SessionStatus::ReplyReceived(WordleEvent::LetterRevealed {
+                    position,
+                    letter,
+                    ..
+                }) if is_woken(session_info) => {
+                    let event = GameSessionEvent::HintRevealed {
+                        position: *position,
+                        letter: *letter,
+                    };
+                    session_info.history.push(HistoryEntry::Hint {
+                        position: *position,
+                        letter: *letter,
+                        payment,
+                    });
+                    if !paid_with_try {
+                        game_session.hint_revenue += msg::value();
+                    }
+                    // 用尝试机会支付时由状态机扣除，尝试次数变化后需要重新计时
+                    advance(
+                        game_session,
+                        user,
+                        Input::HintRevealed { paid_with_try },
+                        None,
+                        Some(event),
+                    );
+                }
+                SessionStatus::WaitUserInput => {
+                    assert!(
+                        session_info.game_type == WORDLE_GAME_TYPE
+                            && session_info.mode == WordleMode::Classic,
+                        "Not a classic Wordle game: RequestHint"
+                    );
+                    assert!(
+                        session_info.hints_used() < config.max_hints,
+                        "No hints left: RequestHint"
+                    );
+                    match payment {
+                        // 用尝试机会支付时不能同时附带金额，否则金额会留在程序中
+                        HintPayment::Try => {
+                            assert!(msg::value() == 0, "Invalid value: RequestHint")
+                        }
+                        HintPayment::Value => assert!(
+                            config.hint_price > 0 && msg::value() == config.hint_price,
+                            "Invalid value: RequestHint"
+                        ),
+                    }
+                    // 请求 Wordle 程序公开一个未知位置的字母；状态机保证用尝试机会支付时至少保留一次猜词机会
+                    let request = Request::Wordle(WordleAction::RevealLetter {
+                        user,
+                        known_positions: session_info.known_positions(),
+                    });
+                    advance(
+                        game_session,
+                        user,
+                        Input::Hint { paid_with_try },
+                        Some(request),
+                        None,
+                    );
+                    exec::wait();
+                }
+                SessionStatus::ReplyReceived(..)
+                | SessionStatus::EngineReplyReceived(..)
+                | SessionStatus::WaitWordleCheckWordReply
+                | SessionStatus::WaitWordleRevealReply
+                | SessionStatus::WaitWordleHintReply
+                | SessionStatus::WaitWordleNextWordReply
+                | SessionStatus::WaitEngineMoveReply => {
+                    panic!("Waiting for a reply: RequestHint");
+                }
+                SessionStatus::Init
+                | SessionStatus::WaitWordleStartReply
+                | SessionStatus::WaitEngineStartReply
+                | SessionStatus::GameOver(..) => {
+                    panic!("User is not in the game: RequestHint");
+                }
+            }
+        }
+        // Action 14: 放弃游戏
+        GameSessionAction::GiveUp => {
+            let user = resolve_user(game_session, ActionKind::GiveUp);
+            let session_info = game_session.sessions.entry(user).or_default();
+            match &session_info.session_status {
+                SessionStatus::ReplyReceived(
+                    wordle_event @ (WordleEvent::WordRevealed { word, .. }
+                    | WordleEvent::ChallengeRevealed { word, .. }),
+                ) if is_woken(session_info) => {
+                    // 挑战另外通知挑战的编号和单词
+                    if let Some(event) = challenge_revealed(wordle_event) {
+                        msg::send(user, event, 0).expect("Failed to send message: GiveUp");
+                    }
+                    let event = GameSessionEvent::GaveUp {
+                        word: Some(word.clone()),
+                    };
+                    advance(game_session, user, Input::Revealed, None, Some(event));
+                }
+                SessionStatus::WaitUserInput if session_info.game_type == WORDLE_GAME_TYPE => {
+                    // 请求 Wordle 程序公开单词
+                    let request = Request::Wordle(WordleAction::RevealWord { user });
+                    advance(game_session, user, Input::GiveUp, Some(request), None);
+                    exec::wait();
+                }
+                SessionStatus::WaitUserInput
+                | SessionStatus::WaitWordleStartReply
+                | SessionStatus::WaitEngineStartReply => {
+                    // 其他游戏或游戏尚未开始，无法公开单词
+                    let event = GameSessionEvent::GaveUp { word: None };
+                    advance(game_session, user, Input::Abandon, None, Some(event));
+                }
+                SessionStatus::ReplyReceived(..)
+                | SessionStatus::EngineReplyReceived(..)
+                | SessionStatus::WaitWordleCheckWordReply
+                | SessionStatus::WaitWordleRevealReply
+                | SessionStatus::WaitWordleHintReply
+                | SessionStatus::WaitWordleNextWordReply
+                | SessionStatus::WaitEngineMoveReply => {
+                    panic!("Waiting for a reply: GiveUp");
+                }
+                SessionStatus::Init | SessionStatus::GameOver(..) => {
+                    panic!("User is not in the game: GiveUp");
+                }
+            }
+        }
+        // Action 15: 放弃当前游戏并开始新游戏
+        GameSessionAction::Restart => {
+            let user = resolve_user(game_session, ActionKind::Restart);
+            let session_info = game_session.sessions.entry(user).or_default();
+            match &session_info.session_status {
+                SessionStatus::WaitUserInput
+                | SessionStatus::WaitWordleStartReply
+                | SessionStatus::WaitEngineStartReply => {
+                    // 当前游戏计为放弃，并退出进行中的比赛
+                    session_info.match_info = None;
+                    advance(game_session, user, Input::Abandon, None, None);
+                }
+                // 新游戏的开始回复由 start_session 处理
+                SessionStatus::ReplyReceived(
+                    WordleEvent::GameStarted { .. } | WordleEvent::HangmanStarted { .. },
+                )
+                | SessionStatus::EngineReplyReceived(EngineEvent::Started { .. })
+                    if is_woken(session_info) => {}
+                SessionStatus::Init | SessionStatus::GameOver(..) => {}
+                SessionStatus::ReplyReceived(..)
+                | SessionStatus::EngineReplyReceived(..)
+                | SessionStatus::WaitWordleCheckWordReply
+                | SessionStatus::WaitWordleRevealReply
+                | SessionStatus::WaitWordleHintReply
+                | SessionStatus::WaitWordleNextWordReply
+                | SessionStatus::WaitEngineMoveReply => {
+                    panic!("Waiting for a reply: Restart");
+                }
+            }
+            let session_info = game_session.sessions.entry(user).or_default();
+            let request = restart_request(&game_session.engines, session_info);
+            start_session(game_session, user, ActionKind::Restart, request, None);
+        }
+        // Action 16: 注册游戏程序
+        GameSessionAction::RegisterEngine {
+            game_type,
+            program_id,
+        } => {
+            assert!(
+                msg::source() == game_session.admin,
+                "Only admin can register engines: RegisterEngine"
+            );
+            assert!(!program_id.is_zero(), "Invalid program_id: RegisterEngine");
+            game_session.engines.insert(game_type, program_id);
+            msg::reply(
+                GameSessionEvent::EngineRegistered {
+                    game_type,
+                    program_id,
+                },
+                0,
+            )
+            .expect("Failed to send reply: RegisterEngine");
+        }
+        // Action 17: 移除游戏程序
+        GameSessionAction::RemoveEngine { game_type } => {
+            assert!(
+                msg::source() == game_session.admin,
+                "Only admin can remove engines: RemoveEngine"
+            );
+            assert!(
+                game_type != WORDLE_GAME_TYPE,
+                "Wordle can not be removed: RemoveEngine"
+            );
+            game_session
+                .engines
+                .remove(&game_type)
+                .expect("Unknown game type: RemoveEngine");
+            msg::reply(GameSessionEvent::EngineRemoved { game_type }, 0)
+                .expect("Failed to send reply: RemoveEngine");
+        }
+        // Action 18: 提出会话密钥，代理账户接受后生效
+        GameSessionAction::CreateSessionKey {
+            key,
+            duration,
+            allowed_actions,
+        } => {
+            let user = msg::source();
+            // 代理账户不能再为其他账户创建密钥
+            assert!(
+                !game_session.session_keys.contains_key(&user),
+                "Session key cannot create keys: CreateSessionKey"
+            );
+            assert!(
+                !key.is_zero() && key != user,
+                "Invalid key: CreateSessionKey"
+            );
+            assert!(
+                duration > 0 && duration <= SESSION_KEY_MAX_DURATION,
+                "Invalid duration: CreateSessionKey"
+            );
+            assert!(
+                !allowed_actions.is_empty(),
+                "No allowed actions: CreateSessionKey"
+            );
+            // 代理账户不能同时为其他用户服务
+            if let Some(session_key) = game_session.session_keys.get(&key) {
+                assert!(
+                    session_key.owner == user,
+                    "Key is already used by another user: CreateSessionKey"
+                );
+            }
+            let expires_at = exec::block_height().saturating_add(duration);
+            game_session.session_key_proposals.insert(
+                (user, key),
+                SessionKey {
+                    owner: user,
+                    expires_at,
+                    allowed_actions,
+                },
+            );
+            // 发送延迟消息，到期后自动删除提议或已接受的密钥
+            msg::send_delayed(
+                exec::program_id(),
+                GameSessionAction::ExpireSessionKey {
+                    owner: user,
+                    key,
+                    expires_at,
+                },
+                0,
+                duration,
+            )
+            .expect("Failed to send delayed message: CreateSessionKey");
+            msg::reply(GameSessionEvent::SessionKeyProposed { key, expires_at }, 0)
+                .expect("Failed to send reply: CreateSessionKey");
+        }
+        // Action 19: 撤销会话密钥（用户本人或代理账户均可撤销），用户也可以撤回尚未接受的提议
+        GameSessionAction::DeleteSessionKey { key } => {
+            let source = msg::source();
+            if game_session
+                .session_key_proposals
+                .remove(&(source, key))
+                .is_some()
+            {
+                msg::reply(GameSessionEvent::SessionKeyDeleted { key }, 0)
+                    .expect("Failed to send reply: DeleteSessionKey");
+                return;
+            }
+            let session_key = game_session
+                .session_keys
+                .get(&key)
+                .expect("Session key does not exist: DeleteSessionKey");
+            assert!(
+                session_key.owner == source || key == source,
+                "Not allowed to delete the key: DeleteSessionKey"
+            );
+            game_session.session_keys.remove(&key);
+            msg::reply(GameSessionEvent::SessionKeyDeleted { key }, 0)
+                .expect("Failed to send reply: DeleteSessionKey");
+        }
+        // Action 20: 会话密钥到期
+        GameSessionAction::ExpireSessionKey {
+            owner,
+            key,
+            expires_at,
+        } => {
+            // 仅处理自身发送的延迟消息，且忽略已被续期的提议和密钥
+            if msg::source() != exec::program_id() {
+                return;
+            }
+            let expired = |session_key: &SessionKey| {
+                session_key.owner == owner && session_key.expires_at == expires_at
+            };
+            if game_session
+                .session_key_proposals
+                .get(&(owner, key))
+                .is_some_and(expired)
+            {
+                game_session.session_key_proposals.remove(&(owner, key));
+            }
+            if game_session.session_keys.get(&key).is_some_and(expired) {
+                game_session.session_keys.remove(&key);
+            }
+        }
+        // Action 21: 协商协议版本，选择双方都支持的最高版本，之后的游戏也使用该版本
+        GameSessionAction::NegotiateProtocol { versions } => {
+            let user = resolve_user(game_session, ActionKind::NegotiateProtocol);
+            let version = ProtocolVersion::negotiate(&versions)
+                .expect("No supported protocol version: NegotiateProtocol");
+            game_session.sessions.entry(user).or_default().protocol = version;
+            msg::reply(GameSessionEvent::ProtocolSelected { version }, 0)
+                .expect("Failed to send reply: NegotiateProtocol");
+        }
+        // Action 22: 管理员提取付费提示的收入
+        GameSessionAction::Withdraw => {
+            assert!(
+                msg::source() == game_session.admin,
+                "Only admin can withdraw: Withdraw"
+            );
+            let amount = game_session.hint_revenue;
+            assert!(amount > 0, "Nothing to withdraw: Withdraw");
+            game_session.hint_revenue = 0;
+            msg::reply(GameSessionEvent::Withdrawn { amount }, amount)
+                .expect("Failed to send reply: Withdraw");
+        }
+        // Action 23: 代理账户接受用户提出的会话密钥，之后才代表用户执行动作
+        GameSessionAction::AcceptSessionKey { owner } => {
+            let key = msg::source();
+            let session_key = game_session
+                .session_key_proposals
+                .remove(&(owner, key))
+                .expect("Session key was not proposed: AcceptSessionKey");
+            // 代理账户不能同时为其他用户服务
+            if let Some(active) = game_session.session_keys.get(&key) {
+                assert!(
+                    active.owner == owner,
+                    "Key is already used by another user: AcceptSessionKey"
+                );
+            }
+            let expires_at = session_key.expires_at;
+            game_session.session_keys.insert(key, session_key);
+            msg::reply(GameSessionEvent::SessionKeyCreated { key, expires_at }, 0)
+                .expect("Failed to send reply: AcceptSessionKey");
+        }
+    }
+}
+
+// 开始 Wordle 程序中的单局游戏
+fn start_wordle_game(
+    game_session: &mut GameSession,
+    kind: ActionKind,
+    mode: WordleMode,
+    pack: PackId,
+) {
+    let user = resolve_user(game_session, kind);
+    let request = StartRequest::Wordle { mode, pack };
+    start_session(game_session, user, kind, request, None);
+}
+
+// 新游戏由哪个程序开始
+enum StartRequest {
+    Wordle {
+        mode: WordleMode,
+        pack: PackId,
+    },
+    Engine {
+        game_type: GameTypeId,
+        program_id: ActorId,
+    },
+}
+
+// 所有开始游戏的动作共用：被开始回复唤醒时通知玩家，否则向游戏程序请求新游戏并等待回复
+fn start_session(
+    game_session: &mut GameSession,
+    user: ActorId,
+    kind: ActionKind,
+    request: StartRequest,
+    match_info: Option<MatchInfo>,
+) {
+    let config = &game_session.config;
+    let session_info = game_session.sessions.entry(user).or_default();
+    if let Some(event) = started_event(session_info) {
+        advance(game_session, user, Input::Started, None, Some(event));
+        return;
+    }
+    // 等待开始回复时可以重新开始
+    assert!(
+        matches!(
+            session_info.session_status,
+            SessionStatus::Init
+                | SessionStatus::GameOver(..)
+                | SessionStatus::WaitWordleStartReply
+                | SessionStatus::WaitEngineStartReply
+        ),
+        "User is already in the game: {kind:?}"
+    );
+    let request = match request {
+        StartRequest::Wordle { mode, pack } => {
+            session_info.game_type = WORDLE_GAME_TYPE;
+            session_info.mode = mode;
+            session_info.pack = pack;
+            Request::Wordle(mode.start_action(user, pack, config))
+        }
+        StartRequest::Engine {
+            game_type,
+            program_id,
+        } => {
+            session_info.game_type = game_type;
+            session_info.mode = WordleMode::default();
+            Request::Engine(program_id, EngineAction::Start { user })
+        }
+    };
+    session_info.match_info = match_info;
+    let tries_limit = session_info.tries_limit();
+    advance(
+        game_session,
+        user,
+        Input::Start { tries_limit },
+        Some(request),
+        None,
+    );
+    // 等待响应
+    exec::wait();
+}
+
+// 开始回复对应发给玩家的事件，挑战同时记录挑战信息；其他状态或其他消息返回 None
+fn started_event(session_info: &mut SessionInfo) -> Option<GameSessionEvent> {
+    if !is_woken(session_info) {
+        return None;
+    }
+    let event = match &session_info.session_status {
+        SessionStatus::ReplyReceived(WordleEvent::GameStarted { .. }) => {
+            GameSessionEvent::StartSuccess
+        }
+        SessionStatus::ReplyReceived(WordleEvent::HangmanStarted { word_length, .. }) => {
+            GameSessionEvent::HangmanStarted {
+                word_length: *word_length,
+            }
+        }
+        SessionStatus::ReplyReceived(WordleEvent::ChallengeStarted {
+            challenge_id,
+            creator,
+            commitment,
+            ..
+        }) => {
+            session_info.challenge = Some(ChallengeInfo {
+                challenge_id: *challenge_id,
+                creator: *creator,
+                commitment: *commitment,
+            });
+            GameSessionEvent::ChallengeStarted {
+                challenge_id: *challenge_id,
+                creator: *creator,
+                commitment: *commitment,
+            }
+        }
+        SessionStatus::EngineReplyReceived(engine_event @ EngineEvent::Started { .. }) => {
+            engine_event.into()
+        }
+        _ => return None,
+    };
+    Some(event)
+}
+
+// 重新开始与上一局相同类型的游戏
+fn restart_request(
+    engines: &HashMap<GameTypeId, ActorId>,
+    session_info: &SessionInfo,
+) -> StartRequest {
+    let game_type = session_info.game_type;
+    match engines.get(&game_type) {
+        Some(program_id) if game_type != WORDLE_GAME_TYPE => StartRequest::Engine {
+            game_type,
+            program_id: *program_id,
+        },
+        // 游戏程序已被移除时回到 Wordle；挑战只能玩一次，改为经典模式
+        _ => {
+            let mode = match session_info.mode {
+                WordleMode::Challenge { .. } => WordleMode::Classic,
+                mode => mode,
+            };
+            StartRequest::Wordle {
+                mode,
+                pack: session_info.pack,
+            }
+        }
+    }
+}
+
+// 发送延迟消息以监控游戏进度，延迟为 GAME_TIMEOUT_BLOCKS
+fn schedule_game_check(user: ActorId, session_id: MessageId) {
+    msg::send_delayed(
+        exec::program_id(),
+        GameSessionAction::CheckGameStatus { user, session_id },
+        0,
+        GAME_TIMEOUT_BLOCKS,
+    )
+    .expect("Failed to send delayed message: CheckGameStatus");
+}
+
+// 若启用每步限时，发送延迟消息检查玩家是否按时猜词，并记录截止区块
+fn schedule_move_check(
+    move_timeout: Option<&MoveTimeout>,
+    user: ActorId,
+    session_info: &mut SessionInfo,
+) {
+    if let Some(move_timeout) = move_timeout {
+        session_info.move_deadline = exec::block_height() + move_timeout.blocks;
+        msg::send_delayed(
+            exec::program_id(),
+            GameSessionAction::CheckMoveStatus {
+                user,
+                session_id: session_info.session_id,
+                tries: session_info.tries,
+            },
+            0,
+            move_timeout.blocks,
+        )
+        .expect("Failed to send delayed message: CheckMoveStatus");
+    }
+}
+
+// 状态机请求游戏程序时发送的消息
+enum Request {
+    Wordle(WordleAction),          // 发送给 Wordle 程序，等待中的消息由回复唤醒
+    Engine(ActorId, EngineAction), // 发送给其他游戏程序，等待中的消息由回复唤醒
+    NextWord,                      // 比赛的下一个单词，没有等待中的消息
+}
+
+// 将输入交给会话状态机：更新尝试次数和会话状态，并执行状态机要求的操作。
+// request 是请求游戏程序时发送的消息，event 是通知玩家的事件；放弃时 event 代替 GameOver
+fn advance(
+    game_session: &mut GameSession,
+    user: ActorId,
+    input: Input,
+    mut request: Option<Request>,
+    mut event: Option<GameSessionEvent>,
+) {
+    let wordle_program_id = game_session.wordle_program_id();
+    let GameSession {
+        sessions,
+        stats,
+        analytics,
+        config,
+        pending_replies,
+        failed_messages,
+        ..
+    } = game_session;
+    let session_info = sessions.entry(user).or_default();
+    let before = session_info.core_state();
+    // 玩家的消息正在等待回复；比赛的下一个单词没有等待中的消息
+    let waiting = session_info.is_wait_reply_status()
+        && !matches!(
+            session_info.session_status,
+            SessionStatus::WaitWordleNextWordReply
+        )
+        && session_info.original_msg_id != msg::id();
+    let original_msg_id = session_info.original_msg_id;
+    let (state, effects) = transition(before, input)
+        .unwrap_or_else(|error| panic!("Invalid session transition: {error:?}"));
+    // 过期的回复和计时器不改变会话
+    if effects.is_empty() {
+        return;
+    }
+    // 进入结束阶段时状态机同时给出结束原因
+    let outcome = finished(&effects);
+    let engine = matches!(request, Some(Request::Engine(..)));
+    let next_word = matches!(request, Some(Request::NextWord));
+    session_info.tries = state.tries;
+    session_info.session_status = match state.phase {
+        Phase::Idle => SessionStatus::Init,
+        Phase::Starting if engine => SessionStatus::WaitEngineStartReply,
+        Phase::Starting if next_word => SessionStatus::WaitWordleNextWordReply,
+        Phase::Starting => SessionStatus::WaitWordleStartReply,
+        Phase::Playing => SessionStatus::WaitUserInput,
+        Phase::Checking if engine => SessionStatus::WaitEngineMoveReply,
+        Phase::Checking => SessionStatus::WaitWordleCheckWordReply,
+        Phase::Revealing => SessionStatus::WaitWordleRevealReply,
+        Phase::Hinting => SessionStatus::WaitWordleHintReply,
+        Phase::Over => SessionStatus::GameOver(outcome.map_or(GameStatus::Lose, Into::into)),
+    };
+    let mut continue_match = false;
+    for effect in effects {
+        match effect {
+            Effect::RequestStart
+            | Effect::RequestMove
+            | Effect::RequestReveal
+            | Effect::RequestHint => {
+                let request = request.take().expect("No request for the transition");
+                if !next_word {
+                    session_info.original_msg_id = msg::id();
+                }
+                let sent_msg_id = send_request(
+                    wordle_program_id,
+                    pending_replies,
+                    session_info,
+                    user,
+                    request,
+                );
+                if effect == Effect::RequestStart {
+                    // 比赛的下一个单词没有玩家的消息，以请求消息作为会话ID
+                    session_info.session_id = if next_word { sent_msg_id } else { msg::id() };
+                    session_info.history.clear();
+                    session_info.challenge = None;
+                }
+            }
+            Effect::ScheduleGameCheck => schedule_game_check(user, session_info.session_id),
+            Effect::ScheduleMoveCheck => {
+                schedule_move_check(config.move_timeout.as_ref(), user, session_info)
+            }
+            Effect::NotifyStarted => {
+                analytics.record_start(exec::block_height());
+                notify(user, event.take());
+            }
+            Effect::NotifyMove | Effect::NotifyHint => notify(user, event.take()),
+            // 有等待中的消息时由它回复失败
+            Effect::NotifyStartFailed if !waiting => {
+                notify(user, Some(GameSessionEvent::StartFailed))
+            }
+            Effect::NotifyRejected if !waiting => {
+                notify(user, Some(GameSessionEvent::ActionFailed))
+            }
+            Effect::NotifyStartFailed | Effect::NotifyRejected => {}
+            Effect::NotifyMoveTimedOut => {
+                let tries = session_info.tries;
+                notify(user, Some(GameSessionEvent::MoveTimedOut { tries }));
+            }
+            Effect::Finish(outcome) => {
+                let stats = stats.entry(user).or_default();
+                continue_match = finish_game(
+                    wordle_program_id,
+                    user,
+                    session_info,
+                    stats,
+                    analytics,
+                    outcome,
+                    before.phase,
+                );
+                if outcome != GameOutcome::Abandoned {
+                    // 挑战结束后请求 Wordle 程序公开单词（放弃时已经公开）
+                    if session_info.challenge.is_some() {
+                        let request = Request::Wordle(WordleAction::RevealWord { user });
+                        send_request(
+                            wordle_program_id,
+                            pending_replies,
+                            session_info,
+                            user,
+                            request,
+                        );
                     }
+                    event = Some(GameSessionEvent::GameOver(outcome.into()));
                 }
+                notify(user, event.take());
             }
         }
     }
+    // 等待中的消息不会再收到回复时唤醒它，由它回复失败，例如请求被拒绝、等待时游戏超时或重新发送了请求
+    if waiting
+        && (!session_info.is_wait_reply_status() || session_info.original_msg_id != original_msg_id)
+    {
+        let event = match before.phase {
+            Phase::Starting => GameSessionEvent::StartFailed,
+            _ => GameSessionEvent::ActionFailed,
+        };
+        failed_messages.insert(original_msg_id, event);
+        exec::wake(original_msg_id).expect("Failed to wake message: advance");
+    }
+    // 比赛中还有单词时自动请求下一个单词
+    if continue_match {
+        let tries_limit = session_info.tries_limit();
+        advance(
+            game_session,
+            user,
+            Input::Start { tries_limit },
+            Some(Request::NextWord),
+            None,
+        );
+    }
+}
+
+// 发送请求并记录等待回复的消息，返回发送的消息ID
+fn send_request(
+    wordle_program_id: ActorId,
+    pending_replies: &mut HashMap<MessageId, ActorId>,
+    session_info: &mut SessionInfo,
+    user: ActorId,
+    request: Request,
+) -> MessageId {
+    let sent_msg_id = match request {
+        Request::Wordle(action) => {
+            session_info.send_to_wordle_msg_id =
+                msg::send(wordle_program_id, action, 0).expect("Failed to send message: Wordle");
+            session_info.send_to_wordle_msg_id
+        }
+        Request::Engine(program_id, action) => {
+            session_info.send_to_engine_msg_id =
+                msg::send(program_id, action, 0).expect("Failed to send message: Engine");
+            session_info.send_to_engine_msg_id
+        }
+        Request::NextWord => {
+            let action = WordleAction::StartGame {
+                user,
+                pack: session_info.pack,
+            };
+            session_info.send_to_wordle_msg_id =
+                msg::send(wordle_program_id, action, 0).expect("Failed to send message: StartGame");
+            session_info.send_to_wordle_msg_id
+        }
+    };
+    pending_replies.insert(sent_msg_id, user);
+    sent_msg_id
+}
+
+// 状态机要求结束本局时返回结束原因
+fn finished(effects: &[Effect]) -> Option<GameOutcome> {
+    effects.iter().find_map(|effect| match effect {
+        Effect::Finish(outcome) => Some(*outcome),
+        _ => None,
+    })
+}
+
+// 通知玩家：处理玩家的消息时回复，处理计时器或游戏程序的回复时直接发送给玩家
+fn notify(user: ActorId, event: Option<GameSessionEvent>) {
+    let Some(event) = event else {
+        return;
+    };
+    if msg::reply_to().is_err() && msg::source() != exec::program_id() {
+        msg::reply(event, 0).expect("Failed to send reply: notify");
+    } else {
+        msg::send(user, event, 0).expect("Failed to send message: notify");
+    }
+}
+
+// 结束当前一局并记录玩家统计和全局统计；返回比赛中是否还有下一个单词
+fn finish_game(
+    wordle_program_id: ActorId,
+    user: ActorId,
+    session_info: &mut SessionInfo,
+    stats: &mut PlayerStats,
+    analytics: &mut GameAnalytics,
+    outcome: GameOutcome,
+    phase: Phase,
+) -> bool {
+    let status: GameStatus = outcome.into();
+    let gave_up = outcome == GameOutcome::Abandoned;
+    // 尚未收到开始回复的游戏没有计入开始次数
+    if phase != Phase::Starting {
+        analytics.record_finish(exec::block_height(), outcome, session_info.tries);
+    }
+    // 只有经典模式按单词包统计
+    let pack = (session_info.game_type == WORDLE_GAME_TYPE
+        && session_info.mode == WordleMode::Classic)
+        .then_some(session_info.pack);
+    stats.record(&status, gave_up, pack);
+    // 向 Wordle 程序报告结果以统计单词难度；公开单词放弃时 Wordle 程序已记录，重新开始放弃的游戏按失败报告
+    let revealed = gave_up && phase != Phase::Playing;
+    if session_info.game_type == WORDLE_GAME_TYPE && session_info.challenge.is_none() && !revealed {
+        msg::send(
+            wordle_program_id,
+            WordleAction::RecordResult {
+                user,
+                won: matches!(status, GameStatus::Win),
+                tries: session_info.tries,
+            },
+            0,
+        )
+        .expect("Failed to send message: RecordResult");
+    }
+    // 通知挑战的创建者
+    if let Some(challenge) = &session_info.challenge {
+        msg::send(
+            challenge.creator,
+            GameSessionEvent::ChallengeCompleted {
+                challenge_id: challenge.challenge_id,
+                player: user,
+                status: status.clone(),
+                tries: session_info.tries,
+            },
+            0,
+        )
+        .expect("Failed to send message: ChallengeCompleted");
+    }
+    let Some(match_info) = session_info.match_info.as_mut() else {
+        return false;
+    };
+    match_info.record(&status, session_info.tries);
+    if match_info.is_over() {
+        msg::send(user, GameSessionEvent::MatchOver(match_info.clone()), 0)
+            .expect("Failed to send message: MatchOver");
+        return false;
+    }
+    true
+}
+
+// 猜词的回复：本次猜词的记录、发给玩家的事件以及是否猜中；其他回复返回 None
+fn check_word_reply(
+    wordle_event: &WordleEvent,
+    word: String,
+    protocol: ProtocolVersion,
+) -> Option<(HistoryEntry, GameSessionEvent, bool)> {
+    match wordle_event {
+        WordleEvent::WordChecked { feedback, .. } => {
+            let (correct_positions, contained_in_word) = (*feedback).into();
+            let entry = HistoryEntry::Guess {
+                word,
+                correct_positions,
+                contained_in_word,
+            };
+            let event = GameSessionEvent::check_word_result(*feedback, protocol);
+            Some((entry, event, feedback.is_solved()))
+        }
+        // 回复只包含此前未猜中的棋盘，全部猜中即获胜
+        WordleEvent::BoardsChecked { boards, .. } => {
+            let solved = boards.iter().all(BoardFeedback::is_solved);
+            let entry = HistoryEntry::Boards {
+                word,
+                boards: boards.clone(),
+            };
+            let event = GameSessionEvent::BoardsResult {
+                boards: boards.clone(),
+            };
+            Some((entry, event, solved))
+        }
+        _ => None,
+    }
+}
+
+// 挑战公开的单词和盐值，其他回复返回 None
+fn challenge_revealed(wordle_event: &WordleEvent) -> Option<GameSessionEvent> {
+    match wordle_event {
+        WordleEvent::ChallengeRevealed {
+            challenge_id,
+            word,
+            salt,
+            ..
+        } => Some(GameSessionEvent::ChallengeRevealed {
+            challenge_id: *challenge_id,
+            word: word.clone(),
+            salt: *salt,
+        }),
+        _ => None,
+    }
+}
+
+// 获取动作所代表的用户：若发送者是代理账户，则代表其授权用户执行
+fn resolve_user(game_session: &GameSession, action: ActionKind) -> ActorId {
+    let source = msg::source();
+    match game_session.session_keys.get(&source) {
+        Some(session_key) => {
+            assert!(
+                session_key.allows(exec::block_height(), action),
+                "Session key is expired or does not allow this action"
+            );
+            session_key.owner
+        }
+        None => source,
+    }
 }
 
 #[no_mangle]
 extern "C" fn handle_reply() {
     let reply_to = msg::reply_to().expect("Failed to query reply_to data: handle_reply");
+    let game_session = get_game_session_mut();
+    // 只处理等待回复的请求，例如忽略记录结果的回复
+    let Some(user) = game_session.pending_replies.remove(&reply_to) else {
+        return;
+    };
+    let Some(session_info) = game_session.sessions.get_mut(&user) else {
+        return;
+    };
+    // 忽略已被新请求取代的回复；Wordle 程序回复 WordleEvent，其他游戏程序回复通用的 EngineEvent
+    let wordle = reply_to == session_info.send_to_wordle_msg_id;
+    if !wordle && reply_to != session_info.send_to_engine_msg_id {
+        return;
+    }
+    // 游戏程序拒绝请求（例如挑战不存在或邀请码错误）时结束等待，会话回到请求之前
+    if msg::reply_code().is_ok_and(|reply_code| reply_code.is_error()) {
+        let input = match session_info.core_state().phase {
+            Phase::Starting => Input::StartFailed,
+            _ => Input::Rejected,
+        };
+        advance(game_session, user, input, None, None);
+        return;
+    }
+    if !wordle {
+        let engine_event: EngineEvent =
+            msg::load().expect("Unable to decode EngineEvent: handle_reply");
+        if *engine_event.get_user() == user && session_info.is_wait_reply_status() {
+            receive_reply(
+                session_info,
+                SessionStatus::EngineReplyReceived(engine_event),
+            );
+        }
+        return;
+    }
     let wordle_event: WordleEvent =
         msg::load().expect("Unable to decode WordleEvent: handle_reply");
-    let game_session = get_game_session_mut();
-    let user = wordle_event.get_user();
-    if let Some(session_info) = game_session.sessions.get_mut(user) {
-        if reply_to == session_info.send_to_wordle_msg_id && session_info.is_wait_reply_status() {
-            session_info.session_status = SessionStatus::ReplyReceived(wordle_event);
-            exec::wake(session_info.original_msg_id).expect("Failed to wake message: handle_reply");
+    // 忽略不属于该用户的回复
+    if *wordle_event.get_user() != user {
+        return;
+    }
+    if session_info.is_wait_reply_status() {
+        receive_reply(session_info, SessionStatus::ReplyReceived(wordle_event));
+    } else if matches!(
+        session_info.session_status,
+        SessionStatus::WaitWordleNextWordReply
+    ) {
+        // 比赛的下一个单词已就绪，没有等待中的消息，直接通知用户
+        let played = session_info
+            .match_info
+            .as_ref()
+            .map_or(0, |match_info| match_info.played);
+        let event = GameSessionEvent::NextWordStarted { word: played + 1 };
+        advance(game_session, user, Input::Started, None, Some(event));
+    } else if matches!(session_info.session_status, SessionStatus::GameOver(..)) {
+        // 挑战结束后公开单词
+        if let Some(event) = challenge_revealed(&wordle_event) {
+            msg::send(user, event, 0).expect("Failed to send message: handle_reply");
         }
     }
 }
 
+// 保存游戏程序的回复并唤醒等待中的消息；回复不改变状态机的阶段，由被唤醒的消息推进状态机
+fn receive_reply(session_info: &mut SessionInfo, status: SessionStatus) {
+    session_info.session_status = status;
+    exec::wake(session_info.original_msg_id).expect("Failed to wake message: handle_reply");
+}
+
+// 保存的回复只能由等待它的消息处理，其他消息仍视为等待回复
+fn is_woken(session_info: &SessionInfo) -> bool {
+    msg::id() == session_info.original_msg_id
+}
+
 #[no_mangle]
 extern "C" fn state() {
+    // 空的查询与元数据一致，返回完整状态
+    let query = if msg::size() == 0 {
+        StateQuery::Full
+    } else {
+        msg::load().expect("Unable to decode StateQuery: state")
+    };
     let game_session = get_game_session();
-    msg::reply::<GameSessionState>(game_session.into(), 0)
-        .expect("Failed to encode or reply from state: state");
+    match query {
+        StateQuery::Full => msg::reply::<GameSessionState>(game_session.into(), 0),
+        StateQuery::Analytics => msg::reply(game_session.analytics.clone(), 0),
+    }
+    .expect("Failed to encode or reply from state: state");
 }
@@ -0,0 +1,33 @@
+// 启用 bench-packs 时生成基准测试用的单词：不重复的五个小写字母组合
+use std::{env, fs, path::Path};
+
+// 生成的单词数
+const WORDS: u64 = 10_000;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_BENCH_PACKS").is_none() {
+        return;
+    }
+    let words: Vec<String> = (0..WORDS).map(word).collect();
+    let source = format!(
+        "pub const WORDS_1K: &[&str] = &{:?};\npub const WORDS_10K: &[&str] = &{:?};\n",
+        &words[..1000],
+        words
+    );
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is not set");
+    fs::write(Path::new(&out_dir).join("bench_words.rs"), source)
+        .expect("Unable to write bench_words.rs");
+}
+
+// 第 i 个单词：乘以与 26^5 互质的数，使单词分布在所有字母组合中
+fn word(i: u64) -> String {
+    let mut n = (i * 7_368_787 + 12_345) % 26u64.pow(5);
+    (0..5)
+        .map(|_| {
+            let letter = (b'a' + (n % 26) as u8) as char;
+            n /= 26;
+            letter
+        })
+        .collect()
+}
@@ -0,0 +1,527 @@
+#![no_std]
+// Wordle 的纯游戏逻辑：单词库、评分、单词校验和会话状态机，供程序和客户端共用
+extern crate alloc;
+
+use alloc::vec::Vec;
+use parity_scale_codec::{Decode, Encode};
+use scale_info::TypeInfo;
+
+// 单词长度
+pub const WORD_LENGTH: usize = 5;
+
+// 单词包ID
+pub type PackId = u32;
+
+// 默认单词包，其他模式也使用该单词包
+pub const DEFAULT_PACK: PackId = 0;
+
+// 单词包的难度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum Difficulty {
+    Easy,   // 简单
+    Medium, // 中等
+    Hard,   // 困难
+}
+
+// 单词包
+#[derive(Debug, Clone, Copy)]
+pub struct WordPack {
+    pub name: &'static str,             // 名称
+    pub difficulty: Difficulty,         // 难度
+    pub words: &'static [&'static str], // 单词
+}
+
+// 所有单词包，下标即单词包ID，DEFAULT_PACK 为默认单词包
+#[cfg(not(feature = "bench-packs"))]
+pub const WORD_PACKS: [WordPack; 4] = BUILTIN_PACKS;
+
+// 启用 bench-packs 时在内置单词包之后加入生成的大单词包，仅用于 gas 基准
+#[cfg(feature = "bench-packs")]
+pub const WORD_PACKS: [WordPack; 6] = {
+    let [classic, common, animals, hard] = BUILTIN_PACKS;
+    [
+        classic,
+        common,
+        animals,
+        hard,
+        WordPack {
+            name: "bench-1k",
+            difficulty: Difficulty::Hard,
+            words: bench::WORDS_1K,
+        },
+        WordPack {
+            name: "bench-10k",
+            difficulty: Difficulty::Hard,
+            words: bench::WORDS_10K,
+        },
+    ]
+};
+
+// 由 build.rs 生成的单词
+#[cfg(feature = "bench-packs")]
+mod bench {
+    include!(concat!(env!("OUT_DIR"), "/bench_words.rs"));
+}
+
+// 内置的单词包
+const BUILTIN_PACKS: [WordPack; 4] = [
+    WordPack {
+        name: "classic",
+        difficulty: Difficulty::Easy,
+        words: &["house", "human", "horse"],
+    },
+    WordPack {
+        name: "common",
+        difficulty: Difficulty::Easy,
+        words: &[
+            "apple", "bread", "chair", "dream", "earth", "green", "light", "money", "water",
+            "world",
+        ],
+    },
+    WordPack {
+        name: "animals",
+        difficulty: Difficulty::Medium,
+        words: &[
+            "camel", "eagle", "llama", "otter", "panda", "sheep", "snake", "tiger", "whale",
+        ],
+    },
+    WordPack {
+        name: "hard",
+        difficulty: Difficulty::Hard,
+        words: &[
+            "crypt", "fjord", "glyph", "jazzy", "nymph", "quaff", "vivid",
+        ],
+    },
+];
+
+// 获取单词包，不存在时为 None
+pub fn word_pack(pack: PackId) -> Option<&'static WordPack> {
+    WORD_PACKS.get(pack as usize)
+}
+
+// 校验单词：五个小写字母
+pub fn is_valid_word(word: &str) -> bool {
+    word.len() == WORD_LENGTH && word.bytes().all(|b| b.is_ascii_lowercase())
+}
+
+// 单词是否在任意单词包中
+pub fn is_known_word(word: &str) -> bool {
+    find_word(word).is_some()
+}
+
+// 单词所在的第一个单词包及其下标，不在任何单词包中时为 None
+pub fn find_word(word: &str) -> Option<(PackId, u32)> {
+    WORD_PACKS.iter().enumerate().find_map(|(pack, word_pack)| {
+        let index = word_pack.words.iter().position(|w| *w == word)?;
+        Some((pack as PackId, index as u32))
+    })
+}
+
+// 定长单词，程序中用于评分，不需要分配字符串
+pub type Word = [u8; WORD_LENGTH];
+
+// 转换为定长单词，长度不是 WORD_LENGTH 时为 None
+pub fn to_word(word: &str) -> Option<Word> {
+    word.as_bytes().try_into().ok()
+}
+
+// 单词中出现的字母集合，每个小写字母一位
+fn letter_set(word: &Word) -> u32 {
+    word.iter().fold(0, |set, &b| set | letter_bit(b))
+}
+
+fn letter_bit(letter: u8) -> u32 {
+    1 << (letter & 0x1f)
+}
+
+// 猜测的反馈：正确的位置和包含在单词中但位置错误的位置
+pub type Feedback = (Vec<u8>, Vec<u8>);
+
+// 比较用户输入与目标单词，返回反馈
+pub fn check_word(key_word: &str, word: &str) -> Feedback {
+    check_word_packed(key_word, word).into()
+}
+
+// 比较用户输入与目标单词，返回紧凑反馈，不分配内存；长度不是 WORD_LENGTH 时没有字母匹配
+pub fn check_word_packed(key_word: &str, word: &str) -> PackedFeedback {
+    match (to_word(key_word), to_word(word)) {
+        (Some(key_word), Some(word)) => score(&key_word, &word),
+        _ => PackedFeedback::default(),
+    }
+}
+
+// 比较两个定长单词，目标单词的字母集合只计算一次
+pub fn score(key_word: &Word, word: &Word) -> PackedFeedback {
+    let letters = letter_set(key_word);
+    let mut feedback = PackedFeedback::default();
+    for (i, (a, b)) in key_word.iter().zip(word).enumerate() {
+        if a == b {
+            feedback = feedback.with_tile(i, Tile::Correct);
+        } else if letters & letter_bit(*b) != 0 {
+            feedback = feedback.with_tile(i, Tile::Present);
+        }
+    }
+    feedback
+}
+
+// 单个字母的反馈
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Absent,  // 不在单词中
+    Present, // 在单词中但位置错误
+    Correct, // 位置正确
+}
+
+// 紧凑的猜测反馈：每个字母 2 位，第 i 个字母位于第 2i 和 2i+1 位，0 为不包含，1 为包含但位置错误，2 为位置正确
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PackedFeedback(pub u16);
+
+impl PackedFeedback {
+    // 所有字母位置正确
+    pub const SOLVED: Self = Self(0b10_1010_1010);
+
+    // 第 i 个字母的反馈
+    pub fn tile(self, i: usize) -> Tile {
+        match (self.0 >> (2 * i)) & 0b11 {
+            2 => Tile::Correct,
+            1 => Tile::Present,
+            _ => Tile::Absent,
+        }
+    }
+
+    // 设置第 i 个字母的反馈
+    pub fn with_tile(self, i: usize, tile: Tile) -> Self {
+        let bits = match tile {
+            Tile::Absent => 0,
+            Tile::Present => 1,
+            Tile::Correct => 2,
+        };
+        Self(self.0 & !(0b11 << (2 * i)) | bits << (2 * i))
+    }
+
+    // 是否猜中
+    pub fn is_solved(self) -> bool {
+        self == Self::SOLVED
+    }
+
+    // 反馈为 tile 的字母数
+    pub fn count(self, tile: Tile) -> usize {
+        (0..WORD_LENGTH).filter(|&i| self.tile(i) == tile).count()
+    }
+}
+
+// 从旧的反馈表示转换
+impl From<&Feedback> for PackedFeedback {
+    fn from((correct_positions, contained_in_word): &Feedback) -> Self {
+        let mut feedback = Self::default();
+        for &i in contained_in_word {
+            feedback = feedback.with_tile(i as usize, Tile::Present);
+        }
+        for &i in correct_positions {
+            feedback = feedback.with_tile(i as usize, Tile::Correct);
+        }
+        feedback
+    }
+}
+
+// 转换为旧的反馈表示
+impl From<PackedFeedback> for Feedback {
+    fn from(feedback: PackedFeedback) -> Self {
+        let positions = |tile| {
+            (0..WORD_LENGTH as u8)
+                .filter(|&i| feedback.tile(i as usize) == tile)
+                .collect()
+        };
+        (positions(Tile::Correct), positions(Tile::Present))
+    }
+}
+
+// 反馈的紧凑表示：第 i 位的三进制数字 2 为位置正确，1 为包含但位置错误，0 为不包含
+pub type Pattern = u8;
+
+// 不同反馈的数量，3^WORD_LENGTH
+pub const PATTERNS: usize = 243;
+
+// 将反馈编码为 Pattern，相同的反馈有相同的编码
+pub fn pattern(feedback: &Feedback) -> Pattern {
+    let (correct_positions, contained_in_word) = feedback;
+    (0..WORD_LENGTH as u8).rev().fold(0, |pattern, i| {
+        let digit = if correct_positions.contains(&i) {
+            2
+        } else if contained_in_word.contains(&i) {
+            1
+        } else {
+            0
+        };
+        pattern * 3 + digit
+    })
+}
+
+// 一局游戏的结束原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+    Won,           // 胜利
+    LostByTries,   // 用尽尝试次数或由游戏程序判负
+    LostByTimeout, // 超时
+    Abandoned,     // 放弃
+}
+
+// 会话阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    Idle,      // 没有游戏
+    Starting,  // 等待游戏程序开始游戏
+    Playing,   // 等待玩家行动
+    Checking,  // 等待游戏程序回复玩家的行动
+    Revealing, // 放弃后等待游戏程序公开单词
+    Hinting,   // 等待游戏程序公开提示的字母
+    Over,      // 游戏结束
+}
+
+// 会话状态机的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionState {
+    pub phase: Phase,            // 阶段
+    pub tries: u8,               // 已使用的尝试次数
+    pub tries_limit: Option<u8>, // 尝试次数限制，None 表示由游戏程序判定胜负
+}
+
+impl SessionState {
+    // 没有游戏的会话
+    pub fn idle() -> Self {
+        Self {
+            phase: Phase::Idle,
+            tries: 0,
+            tries_limit: None,
+        }
+    }
+}
+
+// 游戏程序对一次行动的判定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveResult {
+    Continue, // 继续
+    Won,      // 胜利
+    Lost,     // 失败
+}
+
+// 状态机的输入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Input {
+    Start { tries_limit: Option<u8> },    // 玩家开始游戏
+    Started,                              // 游戏程序已开始游戏
+    StartFailed,                          // 游戏程序拒绝开始游戏
+    Move { resendable: bool }, // 玩家猜词或行动，resendable 表示游戏程序不保存行动，可以重新发送
+    Checked { solved: bool },  // 游戏程序回复猜词结果，由会话按尝试次数判定胜负
+    Judged { result: MoveResult }, // 游戏程序回复行动结果并判定胜负
+    GiveUp,                    // 玩家放弃
+    Abandon,                   // 玩家放弃且不公开单词，例如重新开始或其他游戏
+    Revealed,                  // 游戏程序已公开单词
+    GameTimeout,               // 整局超时
+    MoveTimeout { lose_game: bool }, // 每步超时，lose_game 表示超时直接判负
+    Hint { paid_with_try: bool }, // 玩家请求提示，paid_with_try 表示用一次尝试机会支付
+    HintRevealed { paid_with_try: bool }, // 游戏程序已公开提示的字母
+    Rejected,                  // 游戏程序拒绝了玩家的行动、放弃或提示请求
+}
+
+// 状态变化后会话程序需要执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    RequestStart,        // 请求游戏程序开始游戏
+    ScheduleGameCheck,   // 安排整局超时检查
+    NotifyStarted,       // 通知玩家游戏已开始
+    NotifyStartFailed,   // 通知玩家游戏开始失败
+    RequestMove,         // 将玩家的行动发送给游戏程序
+    NotifyMove,          // 将行动结果通知玩家
+    ScheduleMoveCheck,   // 安排每步超时检查
+    NotifyMoveTimedOut,  // 通知玩家本步超时
+    RequestReveal,       // 请求游戏程序公开单词
+    RequestHint,         // 请求游戏程序公开一个字母
+    NotifyHint,          // 将提示通知玩家
+    NotifyRejected,      // 通知玩家请求被游戏程序拒绝
+    Finish(GameOutcome), // 结束本局并记录结果
+}
+
+// 当前阶段不允许的输入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionError {
+    AlreadyInGame,   // 已在游戏中
+    NotInGame,       // 不在游戏中
+    WaitingForReply, // 正在等待游戏程序回复
+    NotEnoughTries,  // 用尝试机会支付提示后没有剩余的猜词机会
+}
+
+// 会话状态机：根据输入计算新状态和需要执行的操作；过期的回复和计时器被忽略
+pub fn transition(
+    state: SessionState,
+    input: Input,
+) -> Result<(SessionState, Vec<Effect>), TransitionError> {
+    let SessionState {
+        phase,
+        tries,
+        tries_limit,
+    } = state;
+    let next = |phase, tries| SessionState {
+        phase,
+        tries,
+        tries_limit,
+    };
+    let finish = |tries, outcome| {
+        Ok((
+            SessionState {
+                phase: Phase::Over,
+                tries,
+                tries_limit,
+            },
+            alloc::vec![Effect::Finish(outcome)],
+        ))
+    };
+    match (phase, input) {
+        (Phase::Idle | Phase::Starting | Phase::Over, Input::Start { tries_limit }) => Ok((
+            SessionState {
+                phase: Phase::Starting,
+                tries: 0,
+                tries_limit,
+            },
+            alloc::vec![Effect::RequestStart, Effect::ScheduleGameCheck],
+        )),
+        (
+            Phase::Playing | Phase::Checking | Phase::Revealing | Phase::Hinting,
+            Input::Start { .. },
+        ) => Err(TransitionError::AlreadyInGame),
+
+        (Phase::Starting, Input::Started) => Ok((
+            next(Phase::Playing, tries),
+            alloc::vec![Effect::NotifyStarted, Effect::ScheduleMoveCheck],
+        )),
+        (Phase::Starting, Input::StartFailed) => Ok((
+            next(Phase::Idle, tries),
+            alloc::vec![Effect::NotifyStartFailed],
+        )),
+
+        // 等待回复时重复发送的行动会重新发送给游戏程序；游戏程序保存的行动会被执行两次，需要等待回复
+        (Phase::Playing, Input::Move { .. })
+        | (Phase::Checking, Input::Move { resendable: true }) => Ok((
+            next(Phase::Checking, tries),
+            alloc::vec![Effect::RequestMove],
+        )),
+        (Phase::Idle | Phase::Starting | Phase::Revealing | Phase::Over, Input::Move { .. }) => {
+            Err(TransitionError::NotInGame)
+        }
+        (Phase::Checking, Input::Move { resendable: false })
+        | (Phase::Hinting, Input::Move { .. }) => Err(TransitionError::WaitingForReply),
+
+        (Phase::Checking, Input::Checked { solved }) => {
+            let tries = tries.saturating_add(1);
+            if solved {
+                finish(tries, GameOutcome::Won)
+            } else if tries_limit.is_some_and(|limit| tries >= limit) {
+                finish(tries, GameOutcome::LostByTries)
+            } else {
+                Ok((
+                    next(Phase::Playing, tries),
+                    alloc::vec![Effect::NotifyMove, Effect::ScheduleMoveCheck],
+                ))
+            }
+        }
+        (Phase::Checking, Input::Judged { result }) => {
+            let tries = tries.saturating_add(1);
+            match result {
+                MoveResult::Continue => Ok((
+                    next(Phase::Playing, tries),
+                    alloc::vec![Effect::NotifyMove, Effect::ScheduleMoveCheck],
+                )),
+                MoveResult::Won => finish(tries, GameOutcome::Won),
+                MoveResult::Lost => finish(tries, GameOutcome::LostByTries),
+            }
+        }
+
+        // 其他游戏或游戏尚未开始时无法公开单词，直接结束
+        (Phase::Starting, Input::GiveUp) => finish(tries, GameOutcome::Abandoned),
+        (Phase::Playing, Input::GiveUp) => Ok((
+            next(Phase::Revealing, tries),
+            alloc::vec![Effect::RequestReveal],
+        )),
+        (Phase::Checking | Phase::Revealing | Phase::Hinting, Input::GiveUp) => {
+            Err(TransitionError::WaitingForReply)
+        }
+        (Phase::Idle | Phase::Over, Input::GiveUp) => Err(TransitionError::NotInGame),
+        (Phase::Starting | Phase::Playing, Input::Abandon) => finish(tries, GameOutcome::Abandoned),
+        (Phase::Checking | Phase::Revealing | Phase::Hinting, Input::Abandon) => {
+            Err(TransitionError::WaitingForReply)
+        }
+        (Phase::Idle | Phase::Over, Input::Abandon) => Err(TransitionError::NotInGame),
+        (Phase::Revealing, Input::Revealed) => finish(tries, GameOutcome::Abandoned),
+
+        // 至少保留一次猜词机会；尝试机会在公开字母后才扣除
+        (Phase::Playing, Input::Hint { paid_with_try }) => {
+            if paid_with_try && tries_limit.is_some_and(|limit| tries.saturating_add(1) >= limit) {
+                Err(TransitionError::NotEnoughTries)
+            } else {
+                Ok((
+                    next(Phase::Hinting, tries),
+                    alloc::vec![Effect::RequestHint],
+                ))
+            }
+        }
+        (Phase::Idle | Phase::Starting | Phase::Over, Input::Hint { .. }) => {
+            Err(TransitionError::NotInGame)
+        }
+        (Phase::Checking | Phase::Revealing | Phase::Hinting, Input::Hint { .. }) => {
+            Err(TransitionError::WaitingForReply)
+        }
+        // 等待提示时每步限时的计时器被忽略，公开后重新计时
+        (Phase::Hinting, Input::HintRevealed { paid_with_try }) => Ok((
+            next(
+                Phase::Playing,
+                tries.saturating_add(u8::from(paid_with_try)),
+            ),
+            alloc::vec![Effect::NotifyHint, Effect::ScheduleMoveCheck],
+        )),
+
+        // 请求被拒绝时回到等待玩家行动，不消耗尝试次数
+        (Phase::Checking | Phase::Revealing | Phase::Hinting, Input::Rejected) => Ok((
+            next(Phase::Playing, tries),
+            alloc::vec![Effect::NotifyRejected, Effect::ScheduleMoveCheck],
+        )),
+
+        (
+            Phase::Starting | Phase::Playing | Phase::Checking | Phase::Revealing | Phase::Hinting,
+            Input::GameTimeout,
+        ) => finish(tries, GameOutcome::LostByTimeout),
+        // 由游戏程序判定胜负时超时只计入尝试次数，不会因此结束
+        (Phase::Playing, Input::MoveTimeout { lose_game }) => {
+            let tries = tries.saturating_add(1);
+            if lose_game || tries_limit.is_some_and(|limit| tries >= limit) {
+                finish(tries, GameOutcome::LostByTimeout)
+            } else {
+                Ok((
+                    next(Phase::Playing, tries),
+                    alloc::vec![Effect::NotifyMoveTimedOut, Effect::ScheduleMoveCheck],
+                ))
+            }
+        }
+
+        // 过期的回复和计时器
+        (
+            _,
+            Input::Started
+            | Input::StartFailed
+            | Input::Checked { .. }
+            | Input::Judged { .. }
+            | Input::Revealed
+            | Input::GameTimeout
+            | Input::MoveTimeout { .. }
+            | Input::HintRevealed { .. }
+            | Input::Rejected,
+        ) => Ok((state, Vec::new())),
+    }
+}
+
+// 由种子和序号生成确定的随机数（splitmix64），用于在测试中复现游戏
+pub fn seeded_random(seed: u64, nonce: u64) -> u64 {
+    let mut z = seed.wrapping_add(nonce.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,427 @@
+use gword_core::*;
+
+const PHASES: [Phase; 7] = [
+    Phase::Idle,
+    Phase::Starting,
+    Phase::Playing,
+    Phase::Checking,
+    Phase::Revealing,
+    Phase::Hinting,
+    Phase::Over,
+];
+
+// 所有输入，包含每种参数取值
+fn inputs() -> Vec<Input> {
+    vec![
+        Input::Start {
+            tries_limit: Some(5),
+        },
+        Input::Started,
+        Input::StartFailed,
+        Input::Move { resendable: false },
+        Input::Move { resendable: true },
+        Input::Checked { solved: false },
+        Input::Checked { solved: true },
+        Input::Judged {
+            result: MoveResult::Continue,
+        },
+        Input::Judged {
+            result: MoveResult::Won,
+        },
+        Input::Judged {
+            result: MoveResult::Lost,
+        },
+        Input::GiveUp,
+        Input::Abandon,
+        Input::Revealed,
+        Input::GameTimeout,
+        Input::MoveTimeout { lose_game: false },
+        Input::MoveTimeout { lose_game: true },
+        Input::Hint {
+            paid_with_try: false,
+        },
+        Input::Hint {
+            paid_with_try: true,
+        },
+        Input::HintRevealed {
+            paid_with_try: false,
+        },
+        Input::HintRevealed {
+            paid_with_try: true,
+        },
+        Input::Rejected,
+    ]
+}
+
+fn state(phase: Phase, tries: u8) -> SessionState {
+    SessionState {
+        phase,
+        tries,
+        tries_limit: Some(5),
+    }
+}
+
+#[test]
+fn test_check_word() {
+    assert_eq!(check_word("house", "house"), (vec![0, 1, 2, 3, 4], vec![]));
+    assert_eq!(check_word("house", "horse"), (vec![0, 1, 3, 4], vec![]));
+    assert_eq!(check_word("human", "house"), (vec![0], vec![2]));
+    // 重复的字母各自计算
+    assert_eq!(check_word("camel", "llama"), (vec![], vec![0, 1, 2, 3, 4]));
+    assert_eq!(check_word("crypt", "quaff"), (vec![], vec![]));
+}
+
+#[test]
+fn test_packed_feedback() {
+    assert!(check_word_packed("house", "house").is_solved());
+    assert_eq!(check_word_packed("house", "house"), PackedFeedback::SOLVED);
+    let feedback = check_word_packed("human", "house");
+    assert_eq!(feedback.tile(0), Tile::Correct);
+    assert_eq!(feedback.tile(2), Tile::Present);
+    assert_eq!(feedback.tile(4), Tile::Absent);
+    assert_eq!(score(b"human", b"house"), feedback);
+    // 长度不同时没有字母匹配
+    assert_eq!(
+        check_word_packed("house", "hous"),
+        PackedFeedback::default()
+    );
+    assert_eq!(
+        (feedback.count(Tile::Correct), feedback.count(Tile::Present)),
+        (1, 1)
+    );
+    // 与位置列表形式的反馈可以互相转换
+    for (key_word, word) in [("camel", "llama"), ("house", "horse"), ("crypt", "quaff")] {
+        let feedback = check_word(key_word, word);
+        assert_eq!(
+            PackedFeedback::from(&feedback),
+            check_word_packed(key_word, word)
+        );
+        assert_eq!(Feedback::from(check_word_packed(key_word, word)), feedback);
+    }
+}
+
+#[test]
+fn test_word_validation() {
+    assert!(is_valid_word("house"));
+    assert!(!is_valid_word("hous"));
+    assert!(!is_valid_word("houses"));
+    assert!(!is_valid_word("House"));
+    assert!(!is_valid_word("hous3"));
+    assert!(!is_valid_word("maña"));
+
+    assert!(is_known_word("fjord"));
+    assert!(!is_known_word("zebra"));
+    assert_eq!(find_word("horse"), Some((DEFAULT_PACK, 2)));
+    assert_eq!(find_word("zebra"), None);
+    assert_eq!(to_word("house"), Some(*b"house"));
+    assert_eq!(to_word("hous"), None);
+}
+
+#[test]
+fn test_word_packs() {
+    assert_eq!(word_pack(DEFAULT_PACK).unwrap().name, "classic");
+    assert!(word_pack(WORD_PACKS.len() as PackId).is_none());
+    for pack in WORD_PACKS.iter() {
+        assert!(!pack.words.is_empty(), "{} is empty", pack.name);
+        for (i, word) in pack.words.iter().enumerate() {
+            assert!(is_valid_word(word), "{} is invalid", word);
+            assert!(!pack.words[..i].contains(word), "{} is repeated", word);
+        }
+    }
+}
+
+#[test]
+fn test_seeded_random() {
+    // 相同的种子和序号得到相同的值
+    assert_eq!(seeded_random(2, 0), seeded_random(2, 0));
+    assert_ne!(seeded_random(2, 0), seeded_random(2, 1));
+    assert_ne!(seeded_random(2, 0), seeded_random(3, 0));
+}
+
+#[test]
+fn test_full_game() {
+    let (state, effects) = transition(
+        SessionState::idle(),
+        Input::Start {
+            tries_limit: Some(2),
+        },
+    )
+    .unwrap();
+    assert_eq!(state.phase, Phase::Starting);
+    assert_eq!(
+        effects,
+        vec![Effect::RequestStart, Effect::ScheduleGameCheck]
+    );
+
+    let (state, effects) = transition(state, Input::Started).unwrap();
+    assert_eq!(state.phase, Phase::Playing);
+    assert_eq!(
+        effects,
+        vec![Effect::NotifyStarted, Effect::ScheduleMoveCheck]
+    );
+
+    let (state, effects) = transition(state, Input::Move { resendable: true }).unwrap();
+    assert_eq!(state.phase, Phase::Checking);
+    assert_eq!(effects, vec![Effect::RequestMove]);
+
+    let (state, effects) = transition(state, Input::Checked { solved: false }).unwrap();
+    assert_eq!((state.phase, state.tries), (Phase::Playing, 1));
+    assert_eq!(effects, vec![Effect::NotifyMove, Effect::ScheduleMoveCheck]);
+
+    // 用尽尝试次数
+    let (state, _) = transition(state, Input::Move { resendable: true }).unwrap();
+    let (state, effects) = transition(state, Input::Checked { solved: false }).unwrap();
+    assert_eq!((state.phase, state.tries), (Phase::Over, 2));
+    assert_eq!(effects, vec![Effect::Finish(GameOutcome::LostByTries)]);
+
+    // 结束后可以开始新游戏
+    let (state, _) = transition(
+        state,
+        Input::Start {
+            tries_limit: Some(5),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        state,
+        SessionState {
+            phase: Phase::Starting,
+            tries: 0,
+            tries_limit: Some(5)
+        }
+    );
+}
+
+#[test]
+fn test_outcomes() {
+    let finish = |state, input| transition(state, input).unwrap().1;
+
+    assert_eq!(
+        finish(state(Phase::Checking, 4), Input::Checked { solved: true }),
+        vec![Effect::Finish(GameOutcome::Won)]
+    );
+    assert_eq!(
+        finish(
+            state(Phase::Checking, 0),
+            Input::Judged {
+                result: MoveResult::Lost
+            }
+        ),
+        vec![Effect::Finish(GameOutcome::LostByTries)]
+    );
+    // 由游戏程序判定时不受尝试次数限制
+    assert_eq!(
+        finish(
+            state(Phase::Checking, 9),
+            Input::Judged {
+                result: MoveResult::Continue
+            }
+        ),
+        vec![Effect::NotifyMove, Effect::ScheduleMoveCheck]
+    );
+    assert_eq!(
+        finish(
+            state(Phase::Playing, 0),
+            Input::MoveTimeout { lose_game: true }
+        ),
+        vec![Effect::Finish(GameOutcome::LostByTimeout)]
+    );
+    assert_eq!(
+        finish(
+            state(Phase::Playing, 3),
+            Input::MoveTimeout { lose_game: false }
+        ),
+        vec![Effect::NotifyMoveTimedOut, Effect::ScheduleMoveCheck]
+    );
+    assert_eq!(
+        finish(
+            state(Phase::Playing, 4),
+            Input::MoveTimeout { lose_game: false }
+        ),
+        vec![Effect::Finish(GameOutcome::LostByTimeout)]
+    );
+    // 由游戏程序判定胜负的游戏超时只消耗一次尝试，不受会话的尝试次数限制
+    let judged = SessionState {
+        tries_limit: None,
+        ..state(Phase::Playing, 4)
+    };
+    assert_eq!(
+        transition(judged, Input::MoveTimeout { lose_game: false }),
+        Ok((
+            SessionState { tries: 5, ..judged },
+            vec![Effect::NotifyMoveTimedOut, Effect::ScheduleMoveCheck]
+        ))
+    );
+    assert_eq!(
+        finish(state(Phase::Starting, 0), Input::GiveUp),
+        vec![Effect::Finish(GameOutcome::Abandoned)]
+    );
+    assert_eq!(
+        finish(state(Phase::Playing, 1), Input::GiveUp),
+        vec![Effect::RequestReveal]
+    );
+    // 不公开单词时直接结束
+    for phase in [Phase::Starting, Phase::Playing] {
+        assert_eq!(
+            finish(state(phase, 1), Input::Abandon),
+            vec![Effect::Finish(GameOutcome::Abandoned)]
+        );
+    }
+    assert_eq!(
+        finish(state(Phase::Revealing, 1), Input::Revealed),
+        vec![Effect::Finish(GameOutcome::Abandoned)]
+    );
+    assert_eq!(
+        finish(state(Phase::Starting, 0), Input::StartFailed),
+        vec![Effect::NotifyStartFailed]
+    );
+    // 被拒绝的请求回到等待玩家行动，尝试次数不变
+    for phase in [Phase::Checking, Phase::Revealing, Phase::Hinting] {
+        assert_eq!(
+            transition(state(phase, 2), Input::Rejected),
+            Ok((
+                state(Phase::Playing, 2),
+                vec![Effect::NotifyRejected, Effect::ScheduleMoveCheck]
+            ))
+        );
+    }
+}
+
+#[test]
+fn test_rejected_inputs() {
+    for phase in [
+        Phase::Playing,
+        Phase::Checking,
+        Phase::Revealing,
+        Phase::Hinting,
+    ] {
+        assert_eq!(
+            transition(
+                state(phase, 0),
+                Input::Start {
+                    tries_limit: Some(5)
+                }
+            ),
+            Err(TransitionError::AlreadyInGame)
+        );
+    }
+    for phase in [Phase::Idle, Phase::Starting, Phase::Revealing, Phase::Over] {
+        assert_eq!(
+            transition(state(phase, 0), Input::Move { resendable: true }),
+            Err(TransitionError::NotInGame)
+        );
+    }
+    // 游戏程序保存的行动在等待回复时不能重新发送
+    assert_eq!(
+        transition(state(Phase::Checking, 0), Input::Move { resendable: false }),
+        Err(TransitionError::WaitingForReply)
+    );
+    assert_eq!(
+        transition(state(Phase::Checking, 0), Input::Move { resendable: true }),
+        Ok((state(Phase::Checking, 0), vec![Effect::RequestMove]))
+    );
+    for phase in [Phase::Idle, Phase::Over] {
+        for input in [Input::GiveUp, Input::Abandon] {
+            assert_eq!(
+                transition(state(phase, 0), input),
+                Err(TransitionError::NotInGame)
+            );
+        }
+    }
+    for phase in [Phase::Checking, Phase::Revealing, Phase::Hinting] {
+        for input in [Input::GiveUp, Input::Abandon] {
+            assert_eq!(
+                transition(state(phase, 0), input),
+                Err(TransitionError::WaitingForReply)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_hints() {
+    let paid = |paid_with_try| Input::Hint { paid_with_try };
+    let revealed = |paid_with_try| Input::HintRevealed { paid_with_try };
+
+    // 用尝试机会支付时，公开字母后才扣除一次尝试
+    let (hinting, effects) = transition(state(Phase::Playing, 1), paid(true)).unwrap();
+    assert_eq!((hinting.phase, hinting.tries), (Phase::Hinting, 1));
+    assert_eq!(effects, vec![Effect::RequestHint]);
+    let (playing, effects) = transition(hinting, revealed(true)).unwrap();
+    assert_eq!((playing.phase, playing.tries), (Phase::Playing, 2));
+    assert_eq!(effects, vec![Effect::NotifyHint, Effect::ScheduleMoveCheck]);
+
+    // 付费的提示不消耗尝试机会
+    let (hinting, _) = transition(playing, paid(false)).unwrap();
+    let (playing, _) = transition(hinting, revealed(false)).unwrap();
+    assert_eq!((playing.phase, playing.tries), (Phase::Playing, 2));
+
+    // 至少保留一次猜词机会
+    assert_eq!(
+        transition(state(Phase::Playing, 4), paid(true)),
+        Err(TransitionError::NotEnoughTries)
+    );
+    assert!(transition(state(Phase::Playing, 4), paid(false)).is_ok());
+    // 等待提示时不能猜词或放弃
+    for input in [Input::Move { resendable: true }, Input::GiveUp, paid(false)] {
+        assert_eq!(
+            transition(state(Phase::Hinting, 0), input),
+            Err(TransitionError::WaitingForReply)
+        );
+    }
+}
+
+#[test]
+fn test_transition_invariants() {
+    for phase in PHASES {
+        for tries in 0..=5 {
+            for input in inputs() {
+                let before = state(phase, tries);
+                let Ok((after, effects)) = transition(before, input) else {
+                    // 只有玩家的动作会被拒绝
+                    assert!(
+                        matches!(
+                            input,
+                            Input::Start { .. }
+                                | Input::Move { .. }
+                                | Input::GiveUp
+                                | Input::Abandon
+                                | Input::Hint { .. }
+                        ),
+                        "{:?} rejected in {:?}",
+                        input,
+                        phase
+                    );
+                    continue;
+                };
+                let outcomes: Vec<_> = effects
+                    .iter()
+                    .filter(|effect| matches!(effect, Effect::Finish(..)))
+                    .collect();
+                // 结束本局当且仅当进入结束阶段，且只结束一次
+                assert_eq!(
+                    outcomes.len(),
+                    usize::from(after.phase == Phase::Over && phase != Phase::Over),
+                    "{:?} in {:?}",
+                    input,
+                    phase
+                );
+                // 尝试次数只会增加一次，或在开始新游戏时清零
+                match input {
+                    Input::Start { .. } => assert_eq!(after.tries, 0),
+                    _ => assert!(after.tries == before.tries || after.tries == before.tries + 1),
+                }
+                // 没有操作时状态不变
+                if effects.is_empty() {
+                    assert_eq!(after, before, "{:?} in {:?}", input, phase);
+                }
+                // 过期的回复和计时器不会改变没有游戏或已结束的会话
+                if matches!(phase, Phase::Idle | Phase::Over)
+                    && !matches!(input, Input::Start { .. })
+                {
+                    assert!(effects.is_empty(), "{:?} in {:?}", input, phase);
+                }
+            }
+        }
+    }
+}
@@ -1,36 +1,274 @@
 #![no_std]
 
+use blake2::{digest::consts::U32, Blake2b, Digest};
 use gmeta::*;
 use gstd::*;
 
+// 单词包ID、默认单词包和难度在 gword_core 中定义
+use gword_core::WORD_LENGTH;
+pub use gword_core::{Difficulty, PackId, PackedFeedback, DEFAULT_PACK};
+
 // 定义 Wordle 合约的元数据
 pub struct WordleMetadata;
 
 impl Metadata for WordleMetadata {
-    type Init = (); // 无初始化参数
+    type Init = In<WordleInit>; // 会话程序ID和可选的随机数种子
     type Handle = InOut<Action, Event>; // 处理的操作和对应的事件
     type Others = (); // 无其他类型
     type Reply = (); // 无回复消息
     type Signal = (); // 无信号
-    type State = (); // 无状态
+    type State = Out<WordleState>; // 状态输出类型
+}
+
+// 初始化参数
+#[derive(Debug, Clone, Encode, Decode, TypeInfo)]
+pub struct WordleInit {
+    // 游戏会话程序ID，代表玩家的动作只接受来自该程序的消息
+    pub session_program_id: ActorId,
+    // 随机数种子，指定时单词只由种子和调用顺序决定，仅用于测试中复现游戏；None 时使用链上随机数
+    // 只有启用 test-seed 特性编译的程序接受种子，否则初始化失败
+    pub seed: Option<u64>,
 }
 
-// 用户可以执行的操作
+// 用户可以执行的操作，会话程序通过 gsession_io::WordleAction 使用同一类型
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
 pub enum Action {
-    StartGame { user: ActorId },               // 开始新游戏
-    CheckWord { user: ActorId, word: String }, // 检查猜测的单词
+    StartGame {
+        user: ActorId, // 开始新游戏
+        pack: PackId,  // 单词包
+    },
+    CheckWord {
+        user: ActorId, // 检查猜测的单词
+        word: String,
+    },
+    RevealWord {
+        user: ActorId, // 结束游戏并公开单词
+    },
+    RevealLetter {
+        user: ActorId,            // 公开一个字母作为提示
+        known_positions: Vec<u8>, // 玩家已知的位置，不会被公开
+    },
+    StartHangman {
+        user: ActorId, // 开始猜字母游戏
+        max_wrong: u8, // 允许猜错的字母数
+    },
+    GuessLetter {
+        user: ActorId, // 猜一个字母
+        letter: u8,    // 小写字母
+    },
+    StartMultiGame {
+        user: ActorId, // 开始多棋盘游戏
+        boards: u8,    // 棋盘数：2、4 或 8
+        pack: PackId,  // 单词包，单词数不能少于棋盘数
+    },
+    StartAdversarial {
+        user: ActorId, // 开始对抗游戏：单词不固定，始终保留最多的候选单词
+        pack: PackId,  // 候选单词所在的单词包
+    },
+    StartChallenge {
+        user: ActorId,     // 开始玩家创建的挑战
+        challenge_id: u32, // 挑战ID
+        code: Option<u64>, // 公开挑战的邀请码
+    },
+    RecordResult {
+        user: ActorId, // 记录已结束游戏的结果，用于统计单词难度
+        won: bool,     // 是否猜中
+        tries: u8,     // 尝试次数
+    },
+    // 创建挑战，由玩家直接发送给 Wordle 程序，不属于会话程序的协议。
+    // 创建时只发送单词的承诺，单词在创建者公开之前不出现在链上
+    CreateChallenge {
+        commitment: [u8; 32],    // 单词的承诺：challenge_commitment(word, salt)
+        target: ChallengeTarget, // 可以参加挑战的玩家
+    },
+    // 创建者公开挑战的单词，校验承诺并要求单词在单词库中，公开后才能开始挑战。
+    // 公开后单词出现在链上，应在对方准备开始时再公开
+    OpenChallenge {
+        challenge_id: u32, // 挑战ID
+        word: String,      // 挑战的单词
+        salt: [u8; 32],    // 承诺的盐值
+    },
 }
 
-// 合约可以发出的事件
+// 合约可以发出的事件，会话程序通过 gsession_io::WordleEvent 使用同一类型
 #[derive(Debug, Clone, Encode, Decode, TypeInfo)]
 pub enum Event {
     GameStarted {
         user: ActorId, // 游戏开始事件
     },
     WordChecked {
-        user: ActorId,              // 单词检查事件
-        correct_positions: Vec<u8>, // 正确字母的位置
-        contained_in_word: Vec<u8>, // 包含在单词中但位置错误的字母
+        user: ActorId,            // 单词检查事件
+        feedback: PackedFeedback, // 每个字母的反馈
+    },
+    WordRevealed {
+        user: ActorId, // 单词公开事件
+        word: String,  // 本局的单词
+    },
+    LetterRevealed {
+        user: ActorId, // 字母公开事件
+        position: u8,  // 公开的位置
+        letter: u8,    // 该位置的字母
+    },
+    HangmanStarted {
+        user: ActorId,   // 猜字母游戏开始事件
+        word_length: u8, // 单词长度
+    },
+    LetterChecked {
+        user: ActorId,         // 字母检查事件
+        letter: u8,            // 猜测的字母
+        positions: Vec<u8>,    // 字母在单词中的所有位置
+        wrong_guesses: u8,     // 已猜错的字母数
+        status: HangmanStatus, // 游戏状态
+    },
+    BoardsChecked {
+        user: ActorId,              // 多棋盘单词检查事件
+        boards: Vec<BoardFeedback>, // 每个未猜中棋盘的反馈
+    },
+    ChallengeStarted {
+        user: ActorId,        // 挑战开始事件
+        challenge_id: u32,    // 挑战ID
+        creator: ActorId,     // 挑战的创建者
+        commitment: [u8; 32], // 单词的承诺
+    },
+    ChallengeRevealed {
+        user: ActorId,     // 挑战单词公开事件
+        challenge_id: u32, // 挑战ID
+        word: String,      // 挑战的单词
+        salt: [u8; 32],    // 承诺的盐值，用于校验单词与承诺一致
+    },
+    ResultRecorded {
+        user: ActorId, // 结果已记录
+    },
+    // 回复给挑战的创建者，不属于会话程序的协议
+    ChallengeCreated {
+        creator: ActorId,  // 挑战创建事件
+        challenge_id: u32, // 挑战ID
+    },
+    // 回复给挑战的创建者，不属于会话程序的协议
+    ChallengeOpened {
+        creator: ActorId,  // 挑战开放事件
+        challenge_id: u32, // 挑战ID
     },
 }
+
+impl Event {
+    // 获取用户ID，挑战创建事件为创建者
+    pub fn get_user(&self) -> &ActorId {
+        match self {
+            Event::GameStarted { user } => user,
+            Event::WordChecked { user, .. } => user,
+            Event::WordRevealed { user, .. } => user,
+            Event::LetterRevealed { user, .. } => user,
+            Event::HangmanStarted { user, .. } => user,
+            Event::LetterChecked { user, .. } => user,
+            Event::BoardsChecked { user, .. } => user,
+            Event::ChallengeStarted { user, .. } => user,
+            Event::ChallengeRevealed { user, .. } => user,
+            Event::ResultRecorded { user } => user,
+            Event::ChallengeCreated { creator, .. } => creator,
+            Event::ChallengeOpened { creator, .. } => creator,
+        }
+    }
+}
+
+// 单词包信息
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct PackInfo {
+    pub id: PackId,             // 单词包ID
+    pub name: String,           // 名称
+    pub difficulty: Difficulty, // 难度
+    pub size: u32,              // 单词数
+}
+
+// Wordle 程序的状态
+#[derive(Debug, Clone, Default, Encode, Decode, TypeInfo)]
+pub struct WordleState {
+    pub packs: Vec<PackInfo>,                         // 可选的单词包
+    pub unseen_words: Vec<(ActorId, PackId, u32)>,    // 每个玩家在每个单词包中尚未玩过的单词数
+    pub word_stats: Vec<(PackId, String, WordStats)>, // 每个单词的统计
+}
+
+// 根据结果划分难度所需的最少局数
+pub const TIER_MIN_GAMES: u32 = 10;
+
+// 单词的统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct WordStats {
+    pub served: u32,      // 出题次数
+    pub wins: u32,        // 猜中次数
+    pub losses: u32,      // 失败次数（包含放弃）
+    pub total_tries: u32, // 猜中时的尝试次数之和
+}
+
+impl WordStats {
+    // 胜率（百分比），没有结果时为 None
+    pub fn win_rate(&self) -> Option<u32> {
+        let games = self.wins + self.losses;
+        (games > 0).then(|| self.wins * 100 / games)
+    }
+
+    // 猜中时的平均尝试次数（乘以 100），没有猜中时为 None
+    pub fn average_tries_x100(&self) -> Option<u32> {
+        (self.wins > 0).then(|| self.total_tries * 100 / self.wins)
+    }
+
+    // 根据胜率划分的难度，局数少于 TIER_MIN_GAMES 时为 None
+    pub fn tier(&self) -> Option<Difficulty> {
+        if self.wins + self.losses < TIER_MIN_GAMES {
+            return None;
+        }
+        let win_rate = self.win_rate()?;
+        Some(if win_rate >= 80 {
+            Difficulty::Easy
+        } else if win_rate >= 50 {
+            Difficulty::Medium
+        } else {
+            Difficulty::Hard
+        })
+    }
+}
+
+// 多棋盘模式中一个棋盘的反馈
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct BoardFeedback {
+    pub board: u8,                  // 棋盘序号
+    pub correct_positions: Vec<u8>, // 正确字母的位置
+    pub contained_in_word: Vec<u8>, // 包含在单词中但位置错误的字母
+}
+
+impl BoardFeedback {
+    // 判断该棋盘是否猜中
+    pub fn is_solved(&self) -> bool {
+        self.correct_positions.len() == WORD_LENGTH
+    }
+}
+
+// 可以参加挑战的玩家
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum ChallengeTarget {
+    Friend(ActorId), // 指定的好友
+    Code([u8; 32]),  // 任何持有邀请码的玩家，只存储邀请码的哈希，创建时不公开邀请码
+}
+
+// 挑战单词的承诺：blake2b-256(word || salt)
+pub fn challenge_commitment(word: &str, salt: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(word.as_bytes());
+    hasher.update(salt);
+    hasher.finalize().into()
+}
+
+// 邀请码的哈希：blake2b-256(code)
+pub fn challenge_code_hash(code: u64) -> [u8; 32] {
+    let mut hasher = Blake2b::<U32>::new();
+    hasher.update(code.to_le_bytes());
+    hasher.finalize().into()
+}
+
+// 猜字母游戏的状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum HangmanStatus {
+    InProgress, // 进行中
+    Won,        // 所有字母都已猜中
+    Lost,       // 猜错次数达到上限
+}
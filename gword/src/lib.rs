@@ -1,23 +1,227 @@
 #![no_std]
+use core::cmp::Reverse;
 use gstd::{collections::HashMap, exec, msg, prelude::*, ActorId};
+use gword_core::{
+    find_word, is_valid_word, score, to_word, word_pack, Tile, Word, WORD_LENGTH, WORD_PACKS,
+};
 use gword_io::*;
 // 全局可变变量，存储游戏状态
 static mut WORDLE: Option<Wordle> = None;
 
-// 单词库
-const BANK_OF_WORDS: [&str; 3] = ["house", "human", "horse"];
+// 获取单词包中的单词
+fn pack_words(pack: PackId) -> &'static [&'static str] {
+    word_pack(pack).expect("Unknown word pack").words
+}
+
+// 单词包中下标为 index 的单词
+fn pack_word(pack: PackId, index: u32) -> Word {
+    to_word(pack_words(pack)[index as usize]).expect("Invalid word in the pack")
+}
+
+// 多棋盘模式允许的棋盘数
+const BOARDS: [u8; 3] = [2, 4, 8];
+
+// 单局游戏
+struct Game {
+    pack: PackId,                        // 单词包
+    words: Vec<u32>,                     // 本局单词在单词包中的下标，每个棋盘一个
+    solved: Vec<bool>,                   // 每个棋盘是否已猜中
+    mode: GameMode,                      // 游戏模式
+    challenge: Option<(u32, Challenge)>, // 玩家创建的挑战
+}
+
+// 游戏模式
+enum GameMode {
+    Classic,    // 经典模式：猜整个单词
+    MultiBoard, // 多棋盘模式：每次猜测同时用于所有未猜中的棋盘
+    // 对抗模式：保留与所有反馈一致的候选单词，words[0] 为其中之一的下标
+    Adversarial {
+        candidates: WordSet,
+    },
+    Hangman {
+        max_wrong: u8,     // 允许猜错的字母数
+        wrong_guesses: u8, // 已猜错的字母数
+        guessed: Vec<u8>,  // 已猜过的字母
+    },
+}
+
+impl Game {
+    // 第 board 个棋盘的单词
+    fn word(&self, board: usize) -> Word {
+        pack_word(self.pack, self.words[board])
+    }
+
+    // 所有棋盘的单词，以逗号分隔
+    fn joined_words(&self) -> String {
+        let words = pack_words(self.pack);
+        let words: Vec<&str> = self.words.iter().map(|&i| words[i as usize]).collect();
+        words.join(",")
+    }
+}
+
+// 单词集合，按单词包下标存储的位图
+struct WordSet {
+    size: usize,    // 单词包的单词数
+    bits: Vec<u64>, // 位图
+}
+
+impl WordSet {
+    // 包含单词包中所有单词的集合
+    fn full(size: usize) -> Self {
+        let mut set = Self::empty(size);
+        for index in 0..size {
+            set.insert(index);
+        }
+        set
+    }
+
+    // 空集合
+    fn empty(size: usize) -> Self {
+        Self {
+            size,
+            bits: vec![0; size.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, index: usize) {
+        self.bits[index / 64] |= 1 << (index % 64);
+    }
+
+    fn contains(&self, index: usize) -> bool {
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn len(&self) -> u32 {
+        self.bits.iter().map(|bits| bits.count_ones()).sum()
+    }
+
+    // 集合中单词的下标
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.size).filter(|index| self.contains(*index))
+    }
+
+    // 随机选择一个不在集合中的单词并加入集合
+    fn draw(&mut self) -> usize {
+        let unseen: Vec<usize> = (0..self.size)
+            .filter(|index| !self.contains(*index))
+            .collect();
+        let index = unseen[get_random_value(unseen.len() as u32) as usize];
+        self.insert(index);
+        index
+    }
+}
+
+// 玩家创建的挑战
+struct Challenge {
+    creator: ActorId,              // 创建者
+    commitment: [u8; 32],          // 单词的承诺
+    target: ChallengeTarget,       // 可以参加挑战的玩家
+    opened: Option<ChallengeWord>, // 创建者公开的单词，公开之前不能开始
+}
+
+// 创建者公开的挑战单词
+struct ChallengeWord {
+    pack: PackId,   // 单词所在的单词包
+    index: u32,     // 单词在单词包中的下标
+    salt: [u8; 32], // 承诺的盐值
+}
+
+impl Challenge {
+    // 挑战的单词和盐值
+    fn word(&self) -> (&'static str, [u8; 32]) {
+        let opened = self.opened.as_ref().expect("The challenge is not opened");
+        (pack_words(opened.pack)[opened.index as usize], opened.salt)
+    }
+}
 
 // Wordle 游戏结构
 #[derive(Default)]
 struct Wordle {
-    games: HashMap<ActorId, String>, // 存储用户与其对应单词的映射
+    games: HashMap<ActorId, Game>, // 存储用户与其对应游戏的映射
+    served: HashMap<(ActorId, PackId), WordSet>, // 每个玩家在每个单词包中已经玩过的单词
+    word_stats: HashMap<(PackId, u32), WordStats>, // 每个单词的统计，按单词包和下标存储
+    challenges: HashMap<u32, Challenge>, // 尚未开始的挑战
+    next_challenge_id: u32,        // 下一个挑战ID
+    session_program_id: ActorId,   // 游戏会话程序ID
+}
+
+impl Wordle {
+    // 代表玩家的动作只接受会话程序发送的消息，否则任何人都可以操作他人的游戏
+    fn check_source(&self, action: &str) {
+        assert!(
+            msg::source() == self.session_program_id,
+            "Only the session program can act for players: {action}"
+        );
+    }
+
+    // 开始有 boards 个随机单词的游戏，只选择玩家在该单词包中没有玩过的单词
+    fn start(&mut self, user: ActorId, pack: PackId, boards: u8, mode: GameMode) {
+        let words = pack_words(pack);
+        assert!(
+            words.len() >= boards as usize,
+            "The word pack has fewer words than boards"
+        );
+        let served = self
+            .served
+            .entry((user, pack))
+            .or_insert_with(|| WordSet::empty(words.len()));
+        // 没有玩过的单词不够所有棋盘时重新开始，保证各个棋盘的单词不同
+        if words.len() - (served.len() as usize) < boards as usize {
+            *served = WordSet::empty(words.len());
+        }
+        let indices: Vec<u32> = (0..boards).map(|_| served.draw() as u32).collect();
+        // 对抗模式的单词不固定，不计入单词统计
+        if !matches!(mode, GameMode::Adversarial { .. }) {
+            for index in &indices {
+                self.word_stats.entry((pack, *index)).or_default().served += 1;
+            }
+        }
+        let game = Game {
+            pack,
+            words: indices,
+            solved: vec![false; boards as usize],
+            mode,
+            challenge: None,
+        };
+        self.games.insert(user, game);
+    }
+
+    // 记录一局游戏中每个单词的结果；多棋盘模式中未猜中的棋盘计为失败
+    fn record(&mut self, game: &Game, won: bool, tries: u8) {
+        if game.challenge.is_some() || matches!(game.mode, GameMode::Adversarial { .. }) {
+            return;
+        }
+        for (board, index) in game.words.iter().enumerate() {
+            let stats = self.word_stats.entry((game.pack, *index)).or_default();
+            if won || game.solved[board] {
+                stats.wins += 1;
+                stats.total_tries += tries as u32;
+            } else {
+                stats.losses += 1;
+            }
+        }
+    }
 }
 
 // 初始化函数，在合约部署时调用
 #[no_mangle]
 extern "C" fn init() {
+    let init: WordleInit = msg::load().expect("Unable to decode WordleInit");
+    // 只有测试版本接受固定种子，正式版本始终使用链上随机数
+    #[cfg(feature = "test-seed")]
     unsafe {
-        WORDLE = Some(Wordle::default());
+        FIXED_SEED = init.seed;
+    }
+    #[cfg(not(feature = "test-seed"))]
+    assert!(
+        init.seed.is_none(),
+        "Fixed seed requires the test-seed feature: init"
+    );
+    unsafe {
+        WORDLE = Some(Wordle {
+            session_program_id: init.session_program_id,
+            ..Default::default()
+        });
     }
 }
 
@@ -31,38 +235,304 @@ extern "C" fn handle() {
 
     // 根据不同的 Action 执行相应的逻辑
     let reply = match action {
-        Action::StartGame { user } => {
-            // 获取随机单词并开始游戏
-            let random_id = get_random_value(BANK_OF_WORDS.len() as u8);
-            let word = BANK_OF_WORDS[random_id as usize];
-            wordle.games.insert(user, word.to_string());
+        Action::StartGame { user, pack } => {
+            wordle.check_source("StartGame");
+            // 从选择的单词包中获取随机单词并开始游戏
+            wordle.start(user, pack, 1, GameMode::Classic);
+            Event::GameStarted { user }
+        }
+        Action::StartMultiGame { user, boards, pack } => {
+            wordle.check_source("StartMultiGame");
+            assert!(BOARDS.contains(&boards), "Invalid number of boards");
+            wordle.start(user, pack, boards, GameMode::MultiBoard);
             Event::GameStarted { user }
         }
+        Action::StartAdversarial { user, pack } => {
+            wordle.check_source("StartAdversarial");
+            let candidates = WordSet::full(pack_words(pack).len());
+            wordle.start(user, pack, 1, GameMode::Adversarial { candidates });
+            Event::GameStarted { user }
+        }
+        Action::CreateChallenge { commitment, target } => {
+            let creator = msg::source();
+            let challenge_id = wordle.next_challenge_id;
+            wordle.next_challenge_id += 1;
+            wordle.challenges.insert(
+                challenge_id,
+                Challenge {
+                    creator,
+                    commitment,
+                    target,
+                    opened: None,
+                },
+            );
+            Event::ChallengeCreated {
+                creator,
+                challenge_id,
+            }
+        }
+        Action::OpenChallenge {
+            challenge_id,
+            word,
+            salt,
+        } => {
+            let creator = msg::source();
+            let challenge = wordle
+                .challenges
+                .get_mut(&challenge_id)
+                .expect("There is no challenge with this id");
+            assert!(
+                challenge.creator == creator,
+                "Only the creator can open the challenge"
+            );
+            assert!(
+                challenge_commitment(&word, &salt) == challenge.commitment,
+                "The word does not match the commitment"
+            );
+            // 挑战的单词必须在单词库中，只存储其所在的单词包和下标
+            let (pack, index) = find_word(&word).expect("The word is not in the dictionary");
+            challenge.opened = Some(ChallengeWord { pack, index, salt });
+            Event::ChallengeOpened {
+                creator,
+                challenge_id,
+            }
+        }
+        Action::StartChallenge {
+            user,
+            challenge_id,
+            code,
+        } => {
+            wordle.check_source("StartChallenge");
+            // 每个挑战只能玩一次，创建者公开单词之后才能开始
+            let challenge = wordle
+                .challenges
+                .remove(&challenge_id)
+                .expect("There is no challenge with this id");
+            assert!(user != challenge.creator, "Cannot play own challenge");
+            let (pack, index) = challenge
+                .opened
+                .as_ref()
+                .map(|opened| (opened.pack, opened.index))
+                .expect("The challenge is not opened");
+            match challenge.target {
+                ChallengeTarget::Friend(friend) => {
+                    assert!(user == friend, "The challenge is for another player")
+                }
+                ChallengeTarget::Code(code_hash) => {
+                    assert!(
+                        code.map(challenge_code_hash) == Some(code_hash),
+                        "Invalid challenge code"
+                    )
+                }
+            }
+            let (creator, commitment) = (challenge.creator, challenge.commitment);
+            wordle.games.insert(
+                user,
+                Game {
+                    pack,
+                    words: vec![index],
+                    solved: vec![false],
+                    mode: GameMode::Classic,
+                    challenge: Some((challenge_id, challenge)),
+                },
+            );
+            Event::ChallengeStarted {
+                user,
+                challenge_id,
+                creator,
+                commitment,
+            }
+        }
+        Action::StartHangman { user, max_wrong } => {
+            wordle.check_source("StartHangman");
+            assert!(max_wrong > 0, "Invalid max_wrong");
+            // 使用同一个单词库开始猜字母游戏
+            wordle.start(
+                user,
+                DEFAULT_PACK,
+                1,
+                GameMode::Hangman {
+                    max_wrong,
+                    wrong_guesses: 0,
+                    guessed: Vec::new(),
+                },
+            );
+            Event::HangmanStarted {
+                user,
+                word_length: WORD_LENGTH as u8,
+            }
+        }
         Action::CheckWord { user, word } => {
-            // 检查单词长度是否为 5
-            if word.len() != 5 {
-                panic!("The length of the word must be 5");
+            wordle.check_source("CheckWord");
+            // 检查单词是否为五个小写字母
+            assert!(is_valid_word(&word), "Invalid word");
+            let guess = to_word(&word).expect("Invalid word");
+            // 获取用户对应的游戏
+            let game = wordle
+                .games
+                .get_mut(&user)
+                .expect("There is no game with this user");
+            match &mut game.mode {
+                GameMode::Classic => Event::WordChecked {
+                    user,
+                    feedback: score(&game.word(0), &guess),
+                },
+                GameMode::MultiBoard => {
+                    // 对所有未猜中的棋盘计算反馈
+                    let mut boards = Vec::new();
+                    for board in 0..game.words.len() {
+                        if game.solved[board] {
+                            continue;
+                        }
+                        let feedback = score(&game.word(board), &guess);
+                        game.solved[board] = feedback.is_solved();
+                        let (correct_positions, contained_in_word) = feedback.into();
+                        boards.push(BoardFeedback {
+                            board: board as u8,
+                            correct_positions,
+                            contained_in_word,
+                        });
+                    }
+                    Event::BoardsChecked { user, boards }
+                }
+                GameMode::Adversarial { candidates } => {
+                    let size = pack_words(game.pack).len();
+                    // 按反馈将候选单词分组，保留最大的一组；
+                    // 数量相同时选择正确位置更少的反馈
+                    let mut buckets: Vec<(PackedFeedback, WordSet)> = Vec::new();
+                    for index in candidates.iter() {
+                        let feedback = score(&pack_word(game.pack, index as u32), &guess);
+                        match buckets.iter_mut().find(|(key, _)| *key == feedback) {
+                            Some((_, set)) => set.insert(index),
+                            None => {
+                                let mut set = WordSet::empty(size);
+                                set.insert(index);
+                                buckets.push((feedback, set));
+                            }
+                        }
+                    }
+                    let (feedback, set) = buckets
+                        .into_iter()
+                        .max_by_key(|(feedback, set)| {
+                            (
+                                set.len(),
+                                Reverse(feedback.count(Tile::Correct)),
+                                Reverse(feedback.count(Tile::Present)),
+                            )
+                        })
+                        .expect("There are no candidate words");
+                    // 保证 words[0] 始终是候选单词之一，供放弃和提示使用
+                    if !set.contains(game.words[0] as usize) {
+                        let index = set.iter().next().expect("There are no candidate words");
+                        game.words[0] = index as u32;
+                    }
+                    *candidates = set;
+                    Event::WordChecked { user, feedback }
+                }
+                GameMode::Hangman { .. } => panic!("The game is in hangman mode"),
             }
-            // 获取用户对应的单词
-            let key_word = wordle
+        }
+        Action::GuessLetter { user, letter } => {
+            wordle.check_source("GuessLetter");
+            assert!(letter.is_ascii_lowercase(), "Invalid letter");
+            let game = wordle
                 .games
-                .get(&user)
+                .get_mut(&user)
+                .expect("There is no game with this user");
+            let key_word = game.word(0);
+            let GameMode::Hangman {
+                max_wrong,
+                wrong_guesses,
+                guessed,
+            } = &mut game.mode
+            else {
+                panic!("The game is not in hangman mode");
+            };
+            assert!(!guessed.contains(&letter), "The letter is already guessed");
+            guessed.push(letter);
+            // 字母在单词中出现的所有位置
+            let positions: Vec<u8> = key_word
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| **b == letter)
+                .map(|(i, _)| i as u8)
+                .collect();
+            if positions.is_empty() {
+                *wrong_guesses += 1;
+            }
+            let status = if key_word.iter().all(|b| guessed.contains(b)) {
+                HangmanStatus::Won
+            } else if *wrong_guesses >= *max_wrong {
+                HangmanStatus::Lost
+            } else {
+                HangmanStatus::InProgress
+            };
+            Event::LetterChecked {
+                user,
+                letter,
+                positions,
+                wrong_guesses: *wrong_guesses,
+                status,
+            }
+        }
+        Action::RevealWord { user } => {
+            wordle.check_source("RevealWord");
+            // 结束用户的游戏并返回单词，放弃计为失败
+            let game = wordle
+                .games
+                .remove(&user)
                 .expect("There is no game with this user");
-            let (mut matched_indices, mut key_indices) =
-                (Vec::with_capacity(5), Vec::with_capacity(5));
-            // 比较用户输入与目标单词
-            for (i, (a, b)) in key_word.chars().zip(word.chars()).enumerate() {
-                if a == b {
-                    matched_indices.push(i as u8);
-                } else if key_word.contains(b) {
-                    key_indices.push(i as u8);
+            wordle.record(&game, false, 0);
+            match game.challenge {
+                // 挑战同时公开盐值，以便校验承诺
+                Some((challenge_id, challenge)) => {
+                    let (word, salt) = challenge.word();
+                    Event::ChallengeRevealed {
+                        user,
+                        challenge_id,
+                        word: word.to_string(),
+                        salt,
+                    }
                 }
+                // 多棋盘模式下的单词以逗号分隔
+                None => Event::WordRevealed {
+                    user,
+                    word: game.joined_words(),
+                },
             }
-
-            Event::WordChecked {
+        }
+        Action::RecordResult { user, won, tries } => {
+            wordle.check_source("RecordResult");
+            // 游戏已结束，记录结果后移除
+            if let Some(game) = wordle.games.remove(&user) {
+                wordle.record(&game, won, tries);
+            }
+            Event::ResultRecorded { user }
+        }
+        Action::RevealLetter {
+            user,
+            known_positions,
+        } => {
+            wordle.check_source("RevealLetter");
+            let key_word = wordle
+                .games
+                .get(&user)
+                .expect("There is no game with this user")
+                .word(0);
+            // 从未知的位置中随机选择一个
+            let unknown_positions: Vec<u8> = (0..WORD_LENGTH as u8)
+                .filter(|i| !known_positions.contains(i))
+                .collect();
+            assert!(
+                !unknown_positions.is_empty(),
+                "All letters are already known"
+            );
+            let random_id = get_random_value(unknown_positions.len() as u32);
+            let position = unknown_positions[random_id as usize];
+            Event::LetterRevealed {
                 user,
-                correct_positions: matched_indices,
-                contained_in_word: key_indices,
+                position,
+                letter: key_word[position as usize],
             }
         }
     };
@@ -71,15 +541,57 @@ extern "C" fn handle() {
     msg::reply(reply, 0).expect("Error in sending a reply");
 }
 
+// 返回程序状态
+#[no_mangle]
+extern "C" fn state() {
+    let wordle = unsafe { WORDLE.as_ref().expect("The program is not initialized") };
+    let state = WordleState {
+        packs: WORD_PACKS
+            .iter()
+            .enumerate()
+            .map(|(id, pack)| PackInfo {
+                id: id as PackId,
+                name: pack.name.to_string(),
+                difficulty: pack.difficulty,
+                size: pack.words.len() as u32,
+            })
+            .collect(),
+        unseen_words: wordle
+            .served
+            .iter()
+            .map(|((user, pack), served)| (*user, *pack, served.size as u32 - served.len()))
+            .collect(),
+        word_stats: wordle
+            .word_stats
+            .iter()
+            .map(|((pack, index), stats)| {
+                (
+                    *pack,
+                    pack_words(*pack)[*index as usize].to_string(),
+                    stats.clone(),
+                )
+            })
+            .collect(),
+    };
+    msg::reply(state, 0).expect("Failed to encode or reply from state: state");
+}
+
 // 随机数种子
-static mut SEED: u8 = 0;
+static mut SEED: u64 = 0;
+// 初始化时指定的种子，指定时不使用链上随机数
+#[cfg(feature = "test-seed")]
+static mut FIXED_SEED: Option<u64> = None;
 
-// 获取随机值的函数
-pub fn get_random_value(range: u8) -> u8 {
+// 获取随机值的函数，范围可以超过 256 以支持大单词包
+pub fn get_random_value(range: u32) -> u32 {
     let seed = unsafe { SEED };
     unsafe { SEED = SEED.wrapping_add(1) };
+    #[cfg(feature = "test-seed")]
+    if let Some(fixed_seed) = unsafe { FIXED_SEED } {
+        return (gword_core::seeded_random(fixed_seed, seed) % range as u64) as u32;
+    }
     let mut random_input: [u8; 32] = exec::program_id().into();
-    random_input[0] = random_input[0].wrapping_add(seed);
+    random_input[0] = random_input[0].wrapping_add(seed as u8);
     let (random, _) = exec::random(random_input).expect("Error in getting random number");
-    random[0] % range
+    u32::from_le_bytes([random[0], random[1], random[2], random[3]]) % range
 }
@@ -0,0 +1,133 @@
+// Wordle 程序的 gas 基准：在 gtest 中测量 StartGame 和 CheckWord 的 gas 消耗
+// 将 GWORD_BASELINE_WASM 设置为旧版本的 gword.opt.wasm 时同时测量旧版本，输出对比并校验没有变差
+use gstd::ActorId;
+use gtest::{Gas, Program, ProgramBuilder, System};
+use gword_io::*;
+use gword_testing::wasm_path;
+use parity_scale_codec::Encode;
+use std::path::Path;
+
+// 部署者，用户从 USER + 1 开始
+const USER: u64 = 42;
+// 每个动作测量的次数
+const ROUNDS: u64 = 20;
+
+// 测量的动作
+const ACTIONS: [&str; 4] = [
+    "StartGame",
+    "CheckWord",
+    "StartAdversarial",
+    "CheckWord (adversarial)",
+];
+
+// 旧版本（单词以字符串保存时）的 Wordle 程序的消息：初始化没有参数，对抗模式不指定单词包
+#[derive(Encode)]
+enum BaselineAction {
+    StartGame {
+        user: ActorId,
+        pack: PackId,
+    },
+    CheckWord {
+        user: ActorId,
+        word: String,
+    },
+    #[codec(index = 7)]
+    StartAdversarial {
+        user: ActorId,
+    },
+}
+
+#[test]
+fn test_gas() {
+    // 测量时由部署者代替会话程序发送动作
+    let init = WordleInit {
+        session_program_id: USER.into(),
+        seed: None,
+    };
+    let current = measure(&wasm_path("gword"), init.encode(), |user| {
+        [
+            Action::StartGame {
+                user,
+                pack: DEFAULT_PACK,
+            }
+            .encode(),
+            Action::CheckWord {
+                user,
+                word: "horse".to_string(),
+            }
+            .encode(),
+            Action::StartAdversarial {
+                user,
+                pack: DEFAULT_PACK,
+            }
+            .encode(),
+            Action::CheckWord {
+                user,
+                word: "house".to_string(),
+            }
+            .encode(),
+        ]
+    });
+    let baseline = std::env::var("GWORD_BASELINE_WASM").ok().map(|path| {
+        measure(Path::new(&path), Vec::new(), |user| {
+            [
+                BaselineAction::StartGame {
+                    user,
+                    pack: DEFAULT_PACK,
+                }
+                .encode(),
+                BaselineAction::CheckWord {
+                    user,
+                    word: "horse".to_string(),
+                }
+                .encode(),
+                BaselineAction::StartAdversarial { user }.encode(),
+                BaselineAction::CheckWord {
+                    user,
+                    word: "house".to_string(),
+                }
+                .encode(),
+            ]
+        })
+    });
+
+    println!("{:<24} {:>14} {:>14}", "action", "gas", "baseline");
+    for (i, action) in ACTIONS.iter().enumerate() {
+        let baseline = baseline
+            .as_ref()
+            .map_or(String::from("-"), |baseline| baseline[i].to_string());
+        println!("{action:<24} {:>14} {baseline:>14}", current[i].to_string());
+    }
+    if let Some(baseline) = baseline {
+        for (i, action) in ACTIONS.iter().enumerate() {
+            assert!(
+                current[i] <= baseline[i],
+                "{action} uses more gas than the baseline: {} > {}",
+                current[i],
+                baseline[i]
+            );
+        }
+    }
+}
+
+// 部署程序并返回每个动作的平均 gas，encode 按程序的版本编码每个用户依次发送的动作
+fn measure(path: &Path, init: Vec<u8>, encode: impl Fn(ActorId) -> [Vec<u8>; 4]) -> [Gas; 4] {
+    let system = System::new();
+    let program = ProgramBuilder::from_file(path).with_id(1).build(&system);
+    assert!(!program.send_bytes(USER, init).main_failed());
+
+    let mut total = [Gas::zero(); 4];
+    for round in 1..=ROUNDS {
+        // 对抗模式对所有候选单词评分
+        for (total, payload) in total.iter_mut().zip(encode((USER + round).into())) {
+            *total += send(&program, payload);
+        }
+    }
+    total.map(|gas| gas / ROUNDS)
+}
+
+fn send(program: &Program, payload: Vec<u8>) -> Gas {
+    let res = program.send_bytes(USER, payload);
+    assert!(!res.main_failed());
+    res.main_gas_burned()
+}
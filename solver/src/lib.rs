@@ -0,0 +1,156 @@
+// Wordle 求解器：根据本局的猜词反馈和提示筛选候选单词，并按期望信息量为下一次猜测排序
+use gsession_io::{HistoryEntry, SessionInfo};
+use gword_core::{check_word, Feedback, PATTERNS, WORD_LENGTH};
+pub use gword_core::{pattern, Pattern};
+
+// 解析反馈，例如 "gy..g"：g 为位置正确，y 为包含但位置错误，. 为不包含
+pub fn parse_feedback(feedback: &str) -> Option<Feedback> {
+    if feedback.len() != WORD_LENGTH {
+        return None;
+    }
+    let (mut correct_positions, mut contained_in_word) = (Vec::new(), Vec::new());
+    for (i, tile) in feedback.chars().enumerate() {
+        match tile.to_ascii_lowercase() {
+            'g' => correct_positions.push(i as u8),
+            'y' => contained_in_word.push(i as u8),
+            '.' | '-' | 'x' => {}
+            _ => return None,
+        }
+    }
+    Some((correct_positions, contained_in_word))
+}
+
+// 猜测 guess 后反馈的熵，即对候选单词能获得的期望信息量（比特）
+pub fn entropy(guess: &str, candidates: &[&str]) -> f64 {
+    let mut counts = [0u32; PATTERNS];
+    for candidate in candidates {
+        counts[pattern(&check_word(candidate, guess)) as usize] += 1;
+    }
+    let total = candidates.len() as f64;
+    counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// 对下一次猜测的评价
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion<'a> {
+    pub word: &'a str,   // 猜测的单词
+    pub entropy: f64,    // 期望信息量（比特）
+    pub candidate: bool, // 是否可能是答案
+}
+
+// 求解器，猜测只从单词库中选择
+#[derive(Debug, Clone)]
+pub struct Solver<'a> {
+    words: &'a [&'a str],
+    candidates: Vec<&'a str>,
+}
+
+impl<'a> Solver<'a> {
+    // 以单词库中的所有单词为候选
+    pub fn new(words: &'a [&'a str]) -> Self {
+        Self {
+            words,
+            candidates: words.to_vec(),
+        }
+    }
+
+    // 应用会话中已有的猜词和提示记录
+    pub fn from_session(words: &'a [&'a str], session_info: &SessionInfo) -> Self {
+        let mut solver = Self::new(words);
+        for entry in &session_info.history {
+            solver.apply(entry);
+        }
+        solver
+    }
+
+    // 应用一条记录，其他模式的记录不约束单词
+    pub fn apply(&mut self, entry: &HistoryEntry) {
+        match entry {
+            HistoryEntry::Guess {
+                word,
+                correct_positions,
+                contained_in_word,
+            } => self.apply_guess(
+                word,
+                &(correct_positions.clone(), contained_in_word.clone()),
+            ),
+            HistoryEntry::Hint {
+                position, letter, ..
+            } => self.apply_hint(*position, *letter),
+            HistoryEntry::Letter { .. }
+            | HistoryEntry::Boards { .. }
+            | HistoryEntry::Move { .. } => {}
+        }
+    }
+
+    // 保留猜测 guess 时会得到相同反馈的候选单词
+    pub fn apply_guess(&mut self, guess: &str, feedback: &Feedback) {
+        let expected = pattern(feedback);
+        self.candidates
+            .retain(|candidate| pattern(&check_word(candidate, guess)) == expected);
+    }
+
+    // 保留 position 处为 letter 的候选单词
+    pub fn apply_hint(&mut self, position: u8, letter: u8) {
+        self.candidates
+            .retain(|candidate| candidate.as_bytes().get(position as usize) == Some(&letter));
+    }
+
+    // 剩余的候选单词
+    pub fn candidates(&self) -> &[&'a str] {
+        &self.candidates
+    }
+
+    // 单词库中的所有单词按期望信息量从高到低排序，相同时优先可能是答案的单词
+    pub fn rank(&self) -> Vec<Suggestion<'a>> {
+        let mut suggestions: Vec<_> = self
+            .words
+            .iter()
+            .map(|&word| Suggestion {
+                word,
+                entropy: entropy(word, &self.candidates),
+                candidate: self.candidates.contains(&word),
+            })
+            .collect();
+        suggestions.sort_by(|a, b| {
+            b.entropy
+                .total_cmp(&a.entropy)
+                .then(b.candidate.cmp(&a.candidate))
+                .then(a.word.cmp(b.word))
+        });
+        suggestions
+    }
+
+    // 最好的下一次猜测，没有候选单词时为 None
+    pub fn best_guess(&self) -> Option<&'a str> {
+        match self.candidates[..] {
+            [] => None,
+            [word] => Some(word),
+            _ => self.rank().first().map(|suggestion| suggestion.word),
+        }
+    }
+}
+
+// 模拟求解器猜测答案的过程，返回所有猜测；答案不在单词库中时可能无法猜中
+pub fn simulate<'a>(words: &'a [&'a str], answer: &str, tries_limit: u8) -> Vec<&'a str> {
+    let mut solver = Solver::new(words);
+    let mut guesses = Vec::new();
+    while guesses.len() < tries_limit as usize {
+        let Some(guess) = solver.best_guess() else {
+            break;
+        };
+        guesses.push(guess);
+        if guess == answer {
+            break;
+        }
+        solver.apply_guess(guess, &check_word(answer, guess));
+    }
+    guesses
+}
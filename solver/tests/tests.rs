@@ -0,0 +1,86 @@
+use gsession_io::{HintPayment, HistoryEntry, SessionInfo, TRIES_LIMIT};
+use gword_core::{check_word, WORD_PACKS};
+use gword_solver::*;
+
+const WORDS: [&str; 3] = ["house", "human", "horse"];
+
+#[test]
+fn test_pattern() {
+    assert_eq!(pattern(&(vec![], vec![])), 0);
+    assert_eq!(pattern(&(vec![0, 1, 2, 3, 4], vec![])), 242);
+    assert_eq!(pattern(&(vec![0], vec![1])), 2 + 3);
+    assert_eq!(parse_feedback("gy..G"), Some((vec![0, 4], vec![1])));
+    assert_eq!(parse_feedback("gy.."), None);
+    assert_eq!(parse_feedback("gyz.g"), None);
+}
+
+#[test]
+fn test_candidates() {
+    let mut solver = Solver::new(&WORDS);
+    solver.apply_guess("house", &check_word("horse", "house"));
+    assert_eq!(solver.candidates(), ["horse"]);
+
+    let mut solver = Solver::new(&WORDS);
+    solver.apply_hint(1, b'u');
+    assert_eq!(solver.candidates(), ["human"]);
+}
+
+#[test]
+fn test_rank() {
+    let solver = Solver::new(&WORDS);
+    let suggestions = solver.rank();
+    assert_eq!(suggestions.len(), WORDS.len());
+    // 每个单词对三个候选单词的反馈都各不相同，按字母顺序排列
+    assert_eq!(suggestions[0].word, "horse");
+    assert!((suggestions[0].entropy - 3f64.log2()).abs() < 1e-9);
+    assert!(suggestions
+        .windows(2)
+        .all(|pair| pair[0].entropy >= pair[1].entropy));
+
+    let mut solver = Solver::new(&WORDS);
+    solver.apply_guess("human", &check_word("horse", "human"));
+    assert_eq!(solver.best_guess(), Some("horse"));
+    assert!(solver
+        .rank()
+        .iter()
+        .all(|suggestion| suggestion.entropy == 0.0));
+}
+
+#[test]
+fn test_from_session() {
+    let session_info = SessionInfo {
+        history: vec![
+            HistoryEntry::Hint {
+                position: 0,
+                letter: b'h',
+                payment: HintPayment::Try,
+            },
+            HistoryEntry::Guess {
+                word: "house".into(),
+                correct_positions: vec![0, 1, 3, 4],
+                contained_in_word: vec![],
+            },
+        ],
+        ..Default::default()
+    };
+    let solver = Solver::from_session(&WORDS, &session_info);
+    assert_eq!(solver.candidates(), ["horse"]);
+    assert_eq!(solver.best_guess(), Some("horse"));
+}
+
+#[test]
+fn test_every_word_solvable() {
+    for pack in WORD_PACKS.iter() {
+        for &answer in pack.words {
+            let guesses = simulate(pack.words, answer, TRIES_LIMIT);
+            assert_eq!(
+                guesses.last(),
+                Some(&answer),
+                "{} in {} not solved: {:?}",
+                answer,
+                pack.name,
+                guesses
+            );
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// 替代 Wordle 程序和其他游戏程序的模拟程序：单词和结果固定，并且可以暂停回复，
+// 以便在测试中观察会话程序等待回复时的状态
+use gsession_io::*;
+use gstd::{ActorId, Decode, Encode};
+use gtest::WasmProgram;
+use gword_core::{check_word_packed, WORD_LENGTH};
+use std::{cell::Cell, rc::Rc};
+
+// 暂停开关，由测试和模拟程序共享，保存暂停前还会正常回复的次数
+#[derive(Debug, Clone)]
+pub struct Stall(Rc<Cell<u32>>);
+
+impl Default for Stall {
+    fn default() -> Self {
+        Self(Rc::new(Cell::new(u32::MAX)))
+    }
+}
+
+impl Stall {
+    // 不再回复
+    pub fn pause(&self) {
+        self.0.set(0);
+    }
+
+    // 再回复 replies 次后暂停
+    pub fn pause_after(&self, replies: u32) {
+        self.0.set(replies);
+    }
+
+    pub fn resume(&self) {
+        self.0.set(u32::MAX);
+    }
+
+    // 本次请求是否暂停
+    fn take(&self) -> bool {
+        match self.0.get() {
+            0 => true,
+            u32::MAX => false,
+            replies => {
+                self.0.set(replies - 1);
+                false
+            }
+        }
+    }
+}
+
+// 每局的单词都是 word 的 Wordle 程序，只支持经典模式
+#[derive(Debug, Clone)]
+pub struct FakeWordle {
+    pub word: String,
+    pub stall: Stall,
+}
+
+impl FakeWordle {
+    pub fn new(word: &str) -> Self {
+        assert_eq!(word.len(), WORD_LENGTH, "Invalid word: FakeWordle");
+        Self {
+            word: word.to_string(),
+            stall: Stall::default(),
+        }
+    }
+
+    fn event(&self, action: WordleAction) -> Result<WordleEvent, &'static str> {
+        Ok(match action {
+            WordleAction::StartGame { user, .. } => WordleEvent::GameStarted { user },
+            WordleAction::CheckWord { user, word } => WordleEvent::WordChecked {
+                user,
+                feedback: check_word_packed(&self.word, &word),
+            },
+            WordleAction::RevealWord { user } => WordleEvent::WordRevealed {
+                user,
+                word: self.word.clone(),
+            },
+            // 公开第一个未知的位置
+            WordleAction::RevealLetter {
+                user,
+                known_positions,
+            } => {
+                let position = (0..WORD_LENGTH as u8)
+                    .find(|position| !known_positions.contains(position))
+                    .ok_or("No letter to reveal")?;
+                WordleEvent::LetterRevealed {
+                    user,
+                    position,
+                    letter: self.word.as_bytes()[position as usize],
+                }
+            }
+            WordleAction::RecordResult { user, .. } => WordleEvent::ResultRecorded { user },
+            _ => return Err("Unsupported action: FakeWordle"),
+        })
+    }
+}
+
+impl WasmProgram for FakeWordle {
+    fn init(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        Ok(None)
+    }
+
+    fn handle(&mut self, payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        let action = WordleAction::decode(&mut &payload[..]).map_err(|_| "Invalid action")?;
+        // 暂停时以不存在的用户回复，会话程序忽略不属于请求用户的回复，保持在等待回复的状态；
+        // 会话程序不等待记录结果的回复，不计入次数
+        let recorded = matches!(action, WordleAction::RecordResult { .. });
+        let event = self.event(action)?;
+        if !recorded && self.stall.take() {
+            let user = ActorId::zero();
+            return Ok(Some(WordleEvent::ResultRecorded { user }.encode()));
+        }
+        Ok(Some(event.encode()))
+    }
+
+    fn handle_reply(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn handle_signal(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<u8>, &'static str> {
+        Ok(Vec::new())
+    }
+}
+
+// 通用协议的游戏程序，每次行动都以 result 结束
+#[derive(Debug, Clone)]
+pub struct FakeEngine {
+    pub result: EngineResult,
+    pub stall: Stall,
+}
+
+impl FakeEngine {
+    pub fn new(result: EngineResult) -> Self {
+        Self {
+            result,
+            stall: Stall::default(),
+        }
+    }
+}
+
+impl WasmProgram for FakeEngine {
+    fn init(&mut self, _payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        Ok(None)
+    }
+
+    fn handle(&mut self, payload: Vec<u8>) -> Result<Option<Vec<u8>>, &'static str> {
+        let action = EngineAction::decode(&mut &payload[..]).map_err(|_| "Invalid action")?;
+        if self.stall.take() {
+            let user = ActorId::zero();
+            return Ok(Some(EngineEvent::Started { user }.encode()));
+        }
+        let event = match action {
+            EngineAction::Start { user } => EngineEvent::Started { user },
+            EngineAction::Move { user, data } => EngineEvent::MoveMade {
+                user,
+                data,
+                result: self.result,
+            },
+        };
+        Ok(Some(event.encode()))
+    }
+
+    fn handle_reply(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn handle_signal(&mut self, _payload: Vec<u8>) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn state(&mut self) -> Result<Vec<u8>, &'static str> {
+        Ok(Vec::new())
+    }
+}
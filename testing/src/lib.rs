@@ -0,0 +1,57 @@
+// 端到端测试的支持库：在 gtest 中部署游戏会话程序和 Wordle 程序，用声明式的场景描述用户的动作、
+// 区块推进以及期望的事件和错误
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+mod fake;
+mod scenario;
+
+pub use fake::{FakeEngine, FakeWordle, Stall};
+pub use scenario::{Expect, Scenario, ScenarioBuilder};
+
+// 程序ID，其他程序从 FIRST_PROGRAM_ID 开始依次部署
+pub const SESSION_ID: u64 = 1;
+pub const WORDLE_ID: u64 = 2;
+pub const FIRST_PROGRAM_ID: u64 = 3;
+// 部署者，同时是游戏会话程序的管理员
+pub const ADMIN: u64 = 50;
+
+// Wordle 程序默认的随机数种子，第一局经典单词包的单词为 human
+pub const DEFAULT_SEED: u64 = 2;
+
+// 编译输出的 wasm 文件：依次查找 GWORD_WASM_DIR、CARGO_TARGET_DIR 和工作区的 target 目录
+pub fn wasm_path(name: &str) -> PathBuf {
+    let file = format!("{name}.opt.wasm");
+    let mut candidates = Vec::new();
+    if let Ok(dir) = env::var("GWORD_WASM_DIR") {
+        candidates.push(Path::new(&dir).join(&file));
+    }
+    let workspace_target = Path::new(env!("CARGO_MANIFEST_DIR")).join("../target");
+    let target_dirs = env::var("CARGO_TARGET_DIR")
+        .map(PathBuf::from)
+        .into_iter()
+        .chain([workspace_target]);
+    for target_dir in target_dirs {
+        for profile in ["release", "debug"] {
+            candidates.push(
+                target_dir
+                    .join("wasm32-unknown-unknown")
+                    .join(profile)
+                    .join(&file),
+            );
+        }
+    }
+    match candidates.iter().find(|path| path.exists()) {
+        Some(path) => path.clone(),
+        None => panic!(
+            "{file} not found, build the programs with `cargo build -r --features gword/test-seed` first. Searched:\n{}",
+            candidates
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+    }
+}
@@ -0,0 +1,462 @@
+// 测试场景：部署程序后按顺序执行步骤，每一步都校验消息是否成功以及期望的事件
+use crate::{
+    wasm_path, FakeEngine, FakeWordle, ADMIN, DEFAULT_SEED, FIRST_PROGRAM_ID, SESSION_ID, WORDLE_ID,
+};
+use gear_core::{
+    ids::{prelude::MessageIdExt, MessageId},
+    message::{Dispatch, DispatchKind, Message, ReplyDetails},
+};
+use gsession_io::*;
+use gstd::{Decode, Encode};
+use gtest::{constants::GAS_ALLOWANCE, CoreLog, Log, Program, ProgramBuilder, RunResult, System};
+use gword_core::word_pack;
+use gword_io::{WordleInit, WordleState};
+use gword_solver::Solver;
+use std::cell::Cell;
+
+// 使用的 Wordle 程序
+enum Wordle {
+    Program { seed: Option<u64> }, // 编译的 gword 程序，None 时使用链上随机数
+    Fake(FakeWordle),
+}
+
+// 注册到游戏会话程序的其他游戏程序
+enum Engine {
+    Program(&'static str), // 编译的程序，以空消息初始化
+    Fake(FakeEngine),
+}
+
+pub struct ScenarioBuilder {
+    config: GameSessionConfig,
+    wordle: Wordle,
+    relay: Option<u64>,
+    engines: Vec<(GameTypeId, Engine)>,
+}
+
+impl ScenarioBuilder {
+    // 游戏会话程序的配置
+    pub fn config(mut self, config: GameSessionConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // Wordle 程序的随机数种子，None 时使用链上随机数
+    pub fn seed(mut self, seed: Option<u64>) -> Self {
+        self.wordle = Wordle::Program { seed };
+        self
+    }
+
+    // 会话程序的请求发给 relay 账户而不是 Wordle 程序，由测试用 Scenario::relay 按任意顺序转发
+    pub fn relay(mut self, relay: u64) -> Self {
+        self.relay = Some(relay);
+        self
+    }
+
+    // 用模拟程序代替 Wordle 程序
+    pub fn fake_wordle(mut self, wordle: FakeWordle) -> Self {
+        self.wordle = Wordle::Fake(wordle);
+        self
+    }
+
+    // 部署编译的游戏程序并注册为 game_type
+    pub fn engine(mut self, game_type: GameTypeId, name: &'static str) -> Self {
+        self.engines.push((game_type, Engine::Program(name)));
+        self
+    }
+
+    // 部署模拟的游戏程序并注册为 game_type
+    pub fn fake_engine(mut self, game_type: GameTypeId, engine: FakeEngine) -> Self {
+        self.engines.push((game_type, Engine::Fake(engine)));
+        self
+    }
+
+    #[track_caller]
+    pub fn build(self) -> Scenario {
+        let system = System::new();
+        system.init_logger();
+        // 转发时 Wordle 程序只接受中转账户的请求
+        let wordle_source = self.relay.unwrap_or(SESSION_ID);
+        match self.wordle {
+            Wordle::Program { seed } => {
+                let wordle = ProgramBuilder::from_file(wasm_path("gword"))
+                    .with_id(WORDLE_ID)
+                    .build(&system);
+                let init = WordleInit {
+                    session_program_id: wordle_source.into(),
+                    seed,
+                };
+                assert!(!wordle.send(ADMIN, init).main_failed());
+            }
+            Wordle::Fake(fake) => {
+                assert!(self.relay.is_none(), "Relay requires the gword program");
+                let wordle = Program::mock_with_id(&system, WORDLE_ID, fake);
+                assert!(!wordle.send_bytes(ADMIN, []).main_failed());
+            }
+        }
+        let session = ProgramBuilder::from_file(wasm_path("gsession"))
+            .with_id(SESSION_ID)
+            .build(&system);
+        let init = GameSessionInit {
+            wordle_program_id: self.relay.unwrap_or(WORDLE_ID).into(),
+            config: self.config,
+        };
+        assert!(!session.send(ADMIN, init).main_failed());
+
+        let scenario = Scenario {
+            system,
+            next_id: Cell::new(FIRST_PROGRAM_ID),
+        };
+        for (game_type, engine) in self.engines {
+            let program_id = match engine {
+                Engine::Program(name) => scenario.deploy(name, ()),
+                Engine::Fake(fake) => {
+                    let program_id = scenario.next_id.replace(scenario.next_id.get() + 1);
+                    let engine = Program::mock_with_id(&scenario.system, program_id, fake);
+                    assert!(!engine.send_bytes(ADMIN, []).main_failed());
+                    program_id
+                }
+            };
+            scenario.send(
+                ADMIN,
+                GameSessionAction::RegisterEngine {
+                    game_type,
+                    program_id: program_id.into(),
+                },
+                Expect::ok(),
+            );
+        }
+        scenario
+    }
+}
+
+// 一步的期望结果，事件默认发给发送者、来自接收消息的程序
+#[derive(Debug, Clone, Default)]
+pub struct Expect {
+    failed: bool,
+    panic: Option<String>,
+    source: Option<u64>,
+    events: Vec<(Option<u64>, Vec<u8>)>,
+    absent: Vec<(Option<u64>, Vec<u8>)>,
+}
+
+impl Expect {
+    // 消息处理成功
+    pub fn ok() -> Self {
+        Self::default()
+    }
+
+    // 消息处理成功，发送者收到 event
+    pub fn event(event: impl Encode) -> Self {
+        Self::ok().and(event)
+    }
+
+    // 消息处理失败
+    pub fn failed() -> Self {
+        Self {
+            failed: true,
+            ..Self::default()
+        }
+    }
+
+    // 消息处理失败，错误信息包含 message
+    pub fn panic(message: &str) -> Self {
+        Self {
+            failed: true,
+            panic: Some(message.to_string()),
+            ..Self::default()
+        }
+    }
+
+    // 发送者还收到 event
+    pub fn and(mut self, event: impl Encode) -> Self {
+        self.events.push((None, event.encode()));
+        self
+    }
+
+    // user 收到 event
+    pub fn to(mut self, user: u64, event: impl Encode) -> Self {
+        self.events.push((Some(user), event.encode()));
+        self
+    }
+
+    // user 没有收到 event
+    pub fn not_to(mut self, user: u64, event: impl Encode) -> Self {
+        self.absent.push((Some(user), event.encode()));
+        self
+    }
+
+    // 事件来自 program 而不是接收消息的程序
+    pub fn from(mut self, program: u64) -> Self {
+        self.source = Some(program);
+        self
+    }
+
+    #[track_caller]
+    fn check(&self, results: &[RunResult], sender: Option<u64>, program: u64) {
+        let log = |dest: Option<u64>, payload: &[u8]| {
+            let dest = dest.or(sender).expect("Expected events need a destination");
+            Log::builder()
+                .source(self.source.unwrap_or(program))
+                .dest(dest)
+                .payload_bytes(payload)
+        };
+        for (dest, payload) in &self.events {
+            let log = log(*dest, payload);
+            assert!(
+                results.iter().any(|res| res.contains(&log)),
+                "Expected {log:?} in {:?}",
+                results.iter().map(RunResult::log).collect::<Vec<_>>()
+            );
+        }
+        for (dest, payload) in &self.absent {
+            let log = log(*dest, payload);
+            assert!(
+                !results.iter().any(|res| res.contains(&log)),
+                "Unexpected {log:?}"
+            );
+        }
+    }
+}
+
+// 部署好的程序和推进中的区块，步骤按调用顺序执行
+pub struct Scenario {
+    system: System,
+    next_id: Cell<u64>,
+}
+
+impl Default for Scenario {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scenario {
+    // 使用默认配置和默认种子的场景
+    #[track_caller]
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> ScenarioBuilder {
+        ScenarioBuilder {
+            config: GameSessionConfig::default(),
+            wordle: Wordle::Program {
+                seed: Some(DEFAULT_SEED),
+            },
+            relay: None,
+            engines: Vec::new(),
+        }
+    }
+
+    pub fn system(&self) -> &System {
+        &self.system
+    }
+
+    #[track_caller]
+    pub fn program(&self, program_id: u64) -> Program<'_> {
+        self.system
+            .get_program(program_id)
+            .unwrap_or_else(|| panic!("Program {program_id} is not deployed"))
+    }
+
+    // 部署编译的程序 name 并以 init 初始化，返回程序ID
+    #[track_caller]
+    pub fn deploy(&self, name: &str, init: impl Encode) -> u64 {
+        let program_id = self.next_id.replace(self.next_id.get() + 1);
+        let program = ProgramBuilder::from_file(wasm_path(name))
+            .with_id(program_id)
+            .build(&self.system);
+        let res = program.send_bytes(ADMIN, init.encode());
+        assert!(!res.main_failed(), "Unable to init {name}: {:?}", res.log());
+        program_id
+    }
+
+    // 用户向游戏会话程序发送动作
+    #[track_caller]
+    pub fn send(&self, user: u64, action: GameSessionAction, expect: Expect) -> &Self {
+        self.send_to(SESSION_ID, user, action, 0, expect)
+    }
+
+    // 用户向游戏会话程序发送附带 value 的动作
+    #[track_caller]
+    pub fn send_value(
+        &self,
+        user: u64,
+        action: GameSessionAction,
+        value: u128,
+        expect: Expect,
+    ) -> &Self {
+        self.send_to(SESSION_ID, user, action, value, expect)
+    }
+
+    // 用户向任意程序发送消息
+    #[track_caller]
+    pub fn send_to(
+        &self,
+        program_id: u64,
+        user: u64,
+        payload: impl Encode,
+        value: u128,
+        expect: Expect,
+    ) -> &Self {
+        let res = self
+            .program(program_id)
+            .send_bytes_with_value(user, payload.encode(), value);
+        check_result(&res, &expect);
+        expect.check(&[res], Some(user), program_id);
+        self
+    }
+
+    // 用户向游戏会话程序发送动作，返回运行结果，不校验
+    pub fn try_send(&self, user: u64, action: GameSessionAction) -> RunResult {
+        self.program(SESSION_ID).send_bytes(user, action.encode())
+    }
+
+    // 推进 blocks 个区块，执行到期的延迟消息；事件默认来自游戏会话程序
+    #[track_caller]
+    pub fn advance(&self, blocks: u32, expect: Expect) -> &Self {
+        let results = self.system.spend_blocks(blocks);
+        expect.check(&results, None, SESSION_ID);
+        self
+    }
+
+    // 将会话程序发给中转账户的请求转发给 Wordle 程序，再以中转账户的名义把 Wordle 程序的回复
+    // （包括错误回复）发回会话程序，返回会话程序处理回复的运行结果
+    #[track_caller]
+    pub fn relay(&self, request: &CoreLog) -> RunResult {
+        let res = self
+            .program(WORDLE_ID)
+            .send_bytes(request.destination(), request.payload());
+        let reply = res
+            .log()
+            .iter()
+            .find(|log| log.reply_to() == Some(res.sent_message_id()))
+            .expect("No reply from the Wordle program");
+        let details = ReplyDetails::new(request.id(), reply.reply_code().expect("No reply code"));
+        let message = Message::new(
+            MessageId::generate_reply(request.id()),
+            request.destination(),
+            request.source(),
+            reply
+                .payload()
+                .to_vec()
+                .try_into()
+                .expect("Payload is too large"),
+            Some(GAS_ALLOWANCE),
+            0,
+            Some(details.into()),
+        );
+        self.system
+            .send_dispatch(Dispatch::new(DispatchKind::Reply, message))
+    }
+
+    // 发送动作并返回游戏会话程序对用户的回复
+    #[track_caller]
+    pub fn reply(&self, user: u64, action: GameSessionAction) -> GameSessionEvent {
+        self.reply_from(SESSION_ID, user, action)
+    }
+
+    // 向任意程序发送消息并解码对用户的回复
+    #[track_caller]
+    pub fn reply_from<E: Decode>(&self, program_id: u64, user: u64, payload: impl Encode) -> E {
+        let res = self.program(program_id).send_bytes(user, payload.encode());
+        assert!(!res.main_failed(), "Unexpected failure: {:?}", res.log());
+        let message_id = res.sent_message_id();
+        let log = res
+            .log()
+            .iter()
+            .find(|log| log.reply_to() == Some(message_id))
+            .expect("No reply");
+        E::decode(&mut log.payload()).expect("Unable to decode the reply")
+    }
+
+    // 用求解器猜词直到猜中，返回尝试次数
+    #[track_caller]
+    pub fn solve(&self, user: u64) -> u8 {
+        loop {
+            let session_info = self.session_info(user);
+            let words = word_pack(session_info.pack).map_or(&[][..], |pack| pack.words);
+            let word = Solver::from_session(words, &session_info)
+                .best_guess()
+                .expect("No word matches the feedback");
+            match self.reply(user, GameSessionAction::CheckWord { word: word.into() }) {
+                GameSessionEvent::GameOver(GameStatus::Win) => {
+                    return self.session_info(user).tries
+                }
+                GameSessionEvent::GameOver(GameStatus::Lose) => panic!("Lost the game"),
+                _ => {}
+            }
+        }
+    }
+
+    // 校验用户的会话状态
+    #[track_caller]
+    pub fn status(&self, user: u64, status: SessionStatus) -> &Self {
+        let session_status = self.session_info(user).session_status;
+        assert_eq!(
+            session_status.encode(),
+            status.encode(),
+            "Expected {status:?}, got {session_status:?}"
+        );
+        self
+    }
+
+    pub fn session_state(&self) -> GameSessionState {
+        self.program(SESSION_ID)
+            .read_state(b"")
+            .expect("Unable to read GameSessionState")
+    }
+
+    pub fn analytics(&self) -> GameAnalytics {
+        self.program(SESSION_ID)
+            .read_state(StateQuery::Analytics)
+            .expect("Unable to read GameAnalytics")
+    }
+
+    // 用户的会话，没有会话时失败
+    #[track_caller]
+    pub fn session_info(&self, user: u64) -> SessionInfo {
+        let user = user.into();
+        self.session_state()
+            .game_sessions
+            .into_iter()
+            .find_map(|(id, session_info)| (id == user).then_some(session_info))
+            .expect("No session for the user")
+    }
+
+    // 用户的统计，没有结束的游戏时为默认值
+    pub fn player_stats(&self, user: u64) -> PlayerStats {
+        let user = user.into();
+        self.session_state()
+            .player_stats
+            .into_iter()
+            .find_map(|(id, stats)| (id == user).then_some(stats))
+            .unwrap_or_default()
+    }
+
+    pub fn wordle_state(&self) -> WordleState {
+        self.program(WORDLE_ID)
+            .read_state(b"")
+            .expect("Unable to read WordleState")
+    }
+}
+
+// 校验消息是否成功处理
+#[track_caller]
+fn check_result(res: &RunResult, expect: &Expect) {
+    assert_eq!(
+        res.main_failed(),
+        expect.failed,
+        "Unexpected result: {:?}",
+        res.log()
+    );
+    if let Some(message) = &expect.panic {
+        assert!(
+            res.log()
+                .iter()
+                .any(|log| String::from_utf8_lossy(log.payload()).contains(message.as_str())),
+            "Expected panic with {message}: {:?}",
+            res.log()
+        );
+    }
+}
@@ -0,0 +1,623 @@
+// 会话状态机的随机测试：多个用户随机地开始各种模式的游戏、猜词、猜字母、请求提示、放弃、重新开始、
+// 伪造超时检查，并穿插区块推进，每一步之后校验游戏会话程序的不变量。一半的用例中会话程序的请求
+// 先进入队列，再按随机的顺序转发给 Wordle 程序，使不同用户的回复交错到达。失败时输出复现用的种子：
+// GWORD_FUZZ_SEED=<种子> GWORD_FUZZ_CASES=1 cargo test -r -p gword-testing --test fuzz
+use gsession_io::*;
+use gstd::{ActorId, Decode, Encode, MessageId};
+use gtest::{CoreLog, RunResult};
+use gword_core::{
+    is_valid_word, seeded_random, transition, word_pack, Input, Phase, DEFAULT_PACK, WORD_PACKS,
+};
+use gword_solver::Solver;
+use gword_testing::*;
+use std::{collections::HashSet, env};
+
+// 默认的用例数和每个用例的步数
+const CASES: u64 = 8;
+const STEPS: usize = 60;
+// 参与的用户数，用户ID从 FIRST_USER 开始
+const USERS: u64 = 16;
+const FIRST_USER: u64 = 100;
+// 不存在的单词包
+const UNKNOWN_PACK: PackId = 99;
+// 每步限时，以及一次最多推进的区块数
+const MOVE_TIMEOUT_BLOCKS: u32 = 25;
+const MAX_ADVANCE: u64 = 60;
+// 每局最多提示次数
+const MAX_HINTS: u8 = 2;
+// 队列模式中代替 Wordle 程序接收请求的账户
+const RELAY: u64 = 60;
+
+// 确定的随机数，由种子和递增的序号生成
+struct Rng {
+    seed: u64,
+    nonce: u64,
+}
+
+impl Rng {
+    fn below(&mut self, n: u64) -> u64 {
+        self.nonce += 1;
+        seeded_random(self.seed, self.nonce) % n
+    }
+
+    fn chance(&mut self, percent: u64) -> bool {
+        self.below(100) < percent
+    }
+
+    fn user(&mut self) -> u64 {
+        FIRST_USER + self.below(USERS)
+    }
+}
+
+#[derive(Debug)]
+enum Step {
+    // 用户发送动作
+    Send {
+        user: u64,
+        action: GameSessionAction,
+    },
+    // 用户伪造只能由会话程序自己发送的超时检查
+    Forge {
+        user: u64,
+        action: GameSessionAction,
+    },
+    // 转发用户最早的排队请求，并把 Wordle 程序的回复发回会话程序
+    Deliver {
+        user: u64,
+    },
+    Advance {
+        blocks: u32,
+    },
+}
+
+// 每一步前后的状态，按用户编号索引
+struct Snapshot {
+    sessions: Vec<Option<SessionInfo>>,
+    stats: Vec<PlayerStats>,
+    analytics: GameAnalytics,
+}
+
+impl Snapshot {
+    fn read(scenario: &Scenario) -> Self {
+        let state = scenario.session_state();
+        let find = |user: u64| ActorId::from(FIRST_USER + user);
+        Self {
+            sessions: (0..USERS)
+                .map(|user| {
+                    state.game_sessions.iter().find_map(|(id, session_info)| {
+                        (*id == find(user)).then(|| session_info.clone())
+                    })
+                })
+                .collect(),
+            stats: (0..USERS)
+                .map(|user| {
+                    state
+                        .player_stats
+                        .iter()
+                        .find_map(|(id, stats)| (*id == find(user)).then(|| stats.clone()))
+                        .unwrap_or_default()
+                })
+                .collect(),
+            analytics: scenario.analytics(),
+        }
+    }
+
+    // 用户的会话，没有会话时为初始状态
+    fn session(&self, user: u64) -> SessionInfo {
+        self.sessions[(user - FIRST_USER) as usize]
+            .clone()
+            .unwrap_or_default()
+    }
+
+    fn stats(&self, user: u64) -> &PlayerStats {
+        &self.stats[(user - FIRST_USER) as usize]
+    }
+}
+
+// 会话程序发给中转账户、尚未转发的请求，按发送顺序排列
+#[derive(Default)]
+struct Queue(Vec<(u64, CoreLog)>);
+
+impl Queue {
+    // 收集运行结果中会话程序发出的请求
+    fn collect(&mut self, results: &[RunResult]) {
+        let (session, relay) = (ActorId::from(SESSION_ID), ActorId::from(RELAY));
+        for log in results.iter().flat_map(RunResult::log) {
+            if log.source() == session && log.destination() == relay {
+                self.0.push((request_user(log.payload()), log.clone()));
+            }
+        }
+    }
+
+    // 取出用户最早的请求，同一用户的请求按顺序到达 Wordle 程序
+    fn take(&mut self, user: u64) -> Option<CoreLog> {
+        let index = self.0.iter().position(|(id, _)| *id == user)?;
+        Some(self.0.remove(index).1)
+    }
+
+    // 请求是否仍在队列中
+    fn contains(&self, message_id: MessageId) -> bool {
+        self.0.iter().any(|(_, log)| log.id() == message_id)
+    }
+}
+
+// 请求所属的用户
+fn request_user(payload: &[u8]) -> u64 {
+    let user = match WordleAction::decode(&mut &payload[..]).expect("Invalid request") {
+        WordleAction::StartGame { user, .. }
+        | WordleAction::CheckWord { user, .. }
+        | WordleAction::RevealWord { user }
+        | WordleAction::RevealLetter { user, .. }
+        | WordleAction::StartHangman { user, .. }
+        | WordleAction::GuessLetter { user, .. }
+        | WordleAction::StartMultiGame { user, .. }
+        | WordleAction::StartAdversarial { user, .. }
+        | WordleAction::StartChallenge { user, .. }
+        | WordleAction::RecordResult { user, .. } => user,
+        WordleAction::CreateChallenge { .. } | WordleAction::OpenChallenge { .. } => {
+            panic!("Unexpected request: {payload:?}")
+        }
+    };
+    users()
+        .find(|id| ActorId::from(*id) == user)
+        .expect("Request for an unknown user")
+}
+
+fn users() -> impl Iterator<Item = u64> {
+    FIRST_USER..FIRST_USER + USERS
+}
+
+fn env_u64(name: &str) -> Option<u64> {
+    env::var(name).ok().map(|value| {
+        value
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid {name}: {value}"))
+    })
+}
+
+// 随机生成下一步，猜词和伪造的检查参考当前的会话；队列中有请求时随机转发其中一个用户的请求
+fn next_step(rng: &mut Rng, snapshot: &Snapshot, queue: &Queue) -> Step {
+    if !queue.0.is_empty() && rng.chance(30) {
+        let (user, _) = queue.0[rng.below(queue.0.len() as u64) as usize];
+        return Step::Deliver { user };
+    }
+    let user = rng.user();
+    let session_info = snapshot.session(user);
+    let send = |action| Step::Send { user, action };
+    let pack = |rng: &mut Rng| {
+        if rng.chance(10) {
+            UNKNOWN_PACK
+        } else {
+            rng.below(WORD_PACKS.len() as u64) as PackId
+        }
+    };
+    match rng.below(100) {
+        0..=3 => send(GameSessionAction::StartGame),
+        4..=11 => send(GameSessionAction::StartGameWithPack { pack: pack(rng) }),
+        12..=15 => send(GameSessionAction::StartHangman),
+        16..=19 => {
+            // 3 个棋盘无效
+            let boards = [2, 4, 8, 3][rng.below(4) as usize];
+            let pack = pack(rng);
+            send(GameSessionAction::StartMultiGame { boards, pack })
+        }
+        20..=46 => {
+            let words = word_pack(session_info.pack)
+                .or(word_pack(DEFAULT_PACK))
+                .expect("No default pack")
+                .words;
+            let word = match rng.below(10) {
+                // 求解器的猜测，使部分游戏能够获胜
+                0..=3 => Solver::from_session(words, &session_info)
+                    .best_guess()
+                    .unwrap_or(words[0])
+                    .to_string(),
+                4..=7 => words[rng.below(words.len() as u64) as usize].to_string(),
+                8 => "zzzzz".to_string(),
+                _ => "Human".to_string(),
+            };
+            send(GameSessionAction::CheckWord { word })
+        }
+        47..=52 => {
+            // 字母较少时会重复猜测，由 Wordle 程序拒绝
+            let letter = b'a' + rng.below(8) as u8;
+            send(GameSessionAction::GuessLetter { letter })
+        }
+        53..=56 => send(GameSessionAction::RequestHint {
+            payment: HintPayment::Try,
+        }),
+        57..=60 => send(GameSessionAction::GiveUp),
+        61..=64 => send(GameSessionAction::Restart),
+        65..=76 => {
+            let victim = rng.user();
+            let victim_info = snapshot.session(victim);
+            let action = if rng.chance(50) {
+                GameSessionAction::CheckGameStatus {
+                    user: victim.into(),
+                    session_id: victim_info.session_id,
+                }
+            } else {
+                GameSessionAction::CheckMoveStatus {
+                    user: victim.into(),
+                    session_id: victim_info.session_id,
+                    tries: victim_info.tries,
+                }
+            };
+            Step::Forge { user, action }
+        }
+        _ => Step::Advance {
+            blocks: 1 + rng.below(MAX_ADVANCE) as u32,
+        },
+    }
+}
+
+// 执行一步，返回所有运行结果；发送动作时只有发送者消息的运行结果
+fn run_step(scenario: &Scenario, queue: &mut Queue, step: &Step) -> Result<Vec<RunResult>, String> {
+    match step {
+        Step::Send { user, action } | Step::Forge { user, action } => {
+            Ok(vec![scenario.try_send(*user, action.clone())])
+        }
+        Step::Deliver { user } => {
+            let request = queue.take(*user).ok_or("No queued request")?;
+            let res = scenario.relay(&request);
+            // 会话程序处理回复以及被唤醒的消息都不应失败
+            if res.main_failed() || res.others_failed() {
+                return Err(format!("Reply failed: {:?}", res.log()));
+            }
+            Ok(vec![res])
+        }
+        Step::Advance { blocks } => {
+            let results = scenario.system().spend_blocks(*blocks);
+            match results.iter().find(|res| res.main_failed()) {
+                Some(res) => Err(format!("Delayed message failed: {:?}", res.log())),
+                None => Ok(results),
+            }
+        }
+    }
+}
+
+// 动作是否应被接受：由 gword_core 的状态机决定，会话程序另外校验模式、单词格式和提示次数
+fn expect_accepted(session_info: &SessionInfo, action: &GameSessionAction) -> Option<bool> {
+    let state = session_info.core_state();
+    let wordle_mode =
+        |mode| session_info.game_type == WORDLE_GAME_TYPE && session_info.mode == mode;
+    let start = |mode: WordleMode| Input::Start {
+        tries_limit: mode.tries_limit(),
+    };
+    let input = match action {
+        GameSessionAction::StartGame | GameSessionAction::StartGameWithPack { .. } => {
+            start(WordleMode::Classic)
+        }
+        GameSessionAction::StartHangman => start(WordleMode::Hangman),
+        GameSessionAction::StartMultiGame { boards, .. } if !matches!(boards, 2 | 4 | 8) => {
+            return Some(false)
+        }
+        GameSessionAction::StartMultiGame { boards, .. } => {
+            start(WordleMode::MultiBoard { boards: *boards })
+        }
+        GameSessionAction::CheckWord { word }
+            if !is_valid_word(word) || wordle_mode(WordleMode::Hangman) =>
+        {
+            return Some(false)
+        }
+        GameSessionAction::CheckWord { .. } => Input::Move {
+            resendable: session_info.mode.is_stateless(),
+        },
+        GameSessionAction::GuessLetter { .. } if !wordle_mode(WordleMode::Hangman) => {
+            return Some(false)
+        }
+        GameSessionAction::GuessLetter { .. } => Input::Move {
+            resendable: session_info.mode.is_stateless(),
+        },
+        GameSessionAction::RequestHint { .. }
+            if !wordle_mode(WordleMode::Classic) || session_info.hints_used() >= MAX_HINTS =>
+        {
+            return Some(false)
+        }
+        GameSessionAction::RequestHint { payment } => Input::Hint {
+            paid_with_try: *payment == HintPayment::Try,
+        },
+        GameSessionAction::GiveUp => Input::GiveUp,
+        // 进行中或等待开始时放弃当前游戏，其他时候直接开始新游戏
+        GameSessionAction::Restart => {
+            return Some(!matches!(
+                state.phase,
+                Phase::Checking | Phase::Revealing | Phase::Hinting
+            ))
+        }
+        _ => return None,
+    };
+    Some(transition(state, input).is_ok())
+}
+
+// 开始游戏的动作能否由 Wordle 程序开始，None 表示不是开始游戏的动作
+fn valid_start(action: &GameSessionAction) -> Option<bool> {
+    match action {
+        GameSessionAction::StartGame | GameSessionAction::StartHangman => Some(true),
+        GameSessionAction::StartGameWithPack { pack } => Some(word_pack(*pack).is_some()),
+        GameSessionAction::StartMultiGame { boards, pack } => {
+            Some(word_pack(*pack).is_some_and(|pack| pack.words.len() >= *boards as usize))
+        }
+        _ => None,
+    }
+}
+
+// 被接受的动作之后的会话是否符合预期，reply 为发送者收到的回复
+fn expect_session(
+    action: &GameSessionAction,
+    reply: Option<&GameSessionEvent>,
+    before: &SessionInfo,
+    after: &SessionInfo,
+) -> bool {
+    let playing = matches!(after.session_status, SessionStatus::WaitUserInput);
+    match (action, reply) {
+        // 开始失败时回到初始状态
+        (_, Some(GameSessionEvent::StartFailed)) => {
+            valid_start(action) != Some(true) && matches!(after.session_status, SessionStatus::Init)
+        }
+        // 请求被拒绝时回到等待玩家行动，不消耗尝试次数
+        (_, Some(GameSessionEvent::ActionFailed)) => playing && after.tries == before.tries,
+        (
+            GameSessionAction::StartGame
+            | GameSessionAction::StartGameWithPack { .. }
+            | GameSessionAction::StartHangman
+            | GameSessionAction::StartMultiGame { .. },
+            _,
+        ) => valid_start(action) == Some(true) && playing && after.tries == 0,
+        (GameSessionAction::Restart, _) => playing && after.tries == 0,
+        (GameSessionAction::CheckWord { .. } | GameSessionAction::GuessLetter { .. }, _) => {
+            after.tries == before.tries + 1
+        }
+        (GameSessionAction::RequestHint { payment }, _) => {
+            playing
+                && after.tries == before.tries + u8::from(*payment == HintPayment::Try)
+                && after.hints_used() == before.hints_used() + 1
+        }
+        (GameSessionAction::GiveUp, _) => matches!(
+            after.session_status,
+            SessionStatus::GameOver(GameStatus::Lose)
+        ),
+        _ => true,
+    }
+}
+
+// 已开始、尚未结束的游戏
+fn started(session_info: &SessionInfo) -> bool {
+    matches!(
+        session_info.core_state().phase,
+        Phase::Playing | Phase::Checking | Phase::Revealing | Phase::Hinting
+    )
+}
+
+// 校验一步前后的不变量；队列模式中 queue 为尚未转发的请求，unstarted 为尚未开始就结束的局数
+fn check_step(
+    step: &Step,
+    res: Option<&RunResult>,
+    prev: &Snapshot,
+    next: &Snapshot,
+    queue: Option<&Queue>,
+    unstarted: u32,
+) -> Result<(), String> {
+    // 收到的回复都已由被唤醒的消息处理，只有请求仍在队列中的会话等待回复；尝试次数不超过限制
+    for user in users() {
+        let session_info = next.session(user);
+        let waiting = session_info.is_wait_reply_status()
+            && queue.is_some_and(|queue| queue.contains(session_info.send_to_wordle_msg_id));
+        if !waiting
+            && !matches!(
+                session_info.session_status,
+                SessionStatus::Init | SessionStatus::WaitUserInput | SessionStatus::GameOver(..)
+            )
+        {
+            return Err(format!(
+                "User {user} is stuck in {:?}",
+                session_info.session_status
+            ));
+        }
+        if session_info
+            .tries_limit()
+            .is_some_and(|limit| session_info.tries > limit)
+        {
+            return Err(format!(
+                "User {user} exceeded the tries limit: {}",
+                session_info.tries
+            ));
+        }
+    }
+
+    // 进入 GameOver 或重新开始放弃当前游戏时恰好记录一局，没有进行中游戏的会话只能由用户自己改变
+    let sender = match step {
+        Step::Send { user, .. } => Some(*user),
+        Step::Forge { .. } | Step::Deliver { .. } | Step::Advance { .. } => None,
+    };
+    let accepted = res.is_some_and(|res| !res.main_failed());
+    for user in users() {
+        let (before, after) = (prev.session(user), next.session(user));
+        let finished = !matches!(before.session_status, SessionStatus::GameOver(..))
+            && matches!(after.session_status, SessionStatus::GameOver(..));
+        let restarted = matches!(
+            step,
+            Step::Send { user: id, action: GameSessionAction::Restart } if *id == user
+        ) && accepted
+            && matches!(before.core_state().phase, Phase::Starting | Phase::Playing);
+        let games_played = next.stats(user).games_played - prev.stats(user).games_played;
+        if games_played != (finished || restarted) as u32 {
+            return Err(format!(
+                "User {user} recorded {games_played} games, finished: {finished}, restarted: {restarted}"
+            ));
+        }
+        let idle = matches!(
+            before.session_status,
+            SessionStatus::Init | SessionStatus::GameOver(..)
+        );
+        if idle && sender != Some(user) && before.encode() != after.encode() {
+            return Err(format!(
+                "Session of user {user} changed without a game: {before:?} -> {after:?}"
+            ));
+        }
+    }
+
+    match (step, res) {
+        (Step::Send { user, action }, Some(res)) => {
+            let (before, after) = (prev.session(*user), next.session(*user));
+            if let Some(accepted) = expect_accepted(&before, action) {
+                if res.main_failed() == accepted {
+                    return Err(format!(
+                        "Expected accepted: {accepted}, got {:?}",
+                        res.log()
+                    ));
+                }
+            }
+            if res.main_failed() {
+                // 失败的动作不改变会话和统计
+                if before.encode() != after.encode()
+                    || prev.stats(*user).encode() != next.stats(*user).encode()
+                {
+                    return Err(format!("Failed action changed the session: {after:?}"));
+                }
+            } else {
+                let reply = res
+                    .log()
+                    .iter()
+                    .find(|log| log.reply_to() == Some(res.sent_message_id()))
+                    .and_then(|log| GameSessionEvent::decode(&mut log.payload()).ok());
+                // 队列模式中请求的回复到达后才能判断结果
+                let queued = queue.is_some() && after.is_wait_reply_status();
+                if !queued && !expect_session(action, reply.as_ref(), &before, &after) {
+                    return Err(format!(
+                        "Unexpected session after {action:?} with {reply:?}: {after:?}"
+                    ));
+                }
+            }
+        }
+        // 伪造的检查被忽略
+        (Step::Forge { .. }, Some(res)) => {
+            if res.main_failed() {
+                return Err(format!("Forged check failed: {:?}", res.log()));
+            }
+            if prev.sessions.encode() != next.sessions.encode()
+                || prev.stats.encode() != next.stats.encode()
+            {
+                return Err("Forged check changed the state".to_string());
+            }
+        }
+        _ => {}
+    }
+
+    // 全局统计与各个会话一致，尚未开始就结束的游戏只计入玩家统计
+    let games_played: u32 = next.stats.iter().map(|stats| stats.games_played).sum();
+    if next.analytics.totals.finished + unstarted != games_played {
+        return Err(format!(
+            "Analytics finished {} games, players {games_played}, unstarted {unstarted}",
+            next.analytics.totals.finished
+        ));
+    }
+    let playing = users().filter(|user| started(&next.session(*user))).count() as u32;
+    if next.analytics.active_games() != playing {
+        return Err(format!(
+            "Analytics has {} active games, players {playing}",
+            next.analytics.active_games()
+        ));
+    }
+    Ok(())
+}
+
+fn run_case(seed: u64) -> Result<(), (Vec<Step>, String)> {
+    let mut rng = Rng { seed, nonce: 0 };
+    // 一半的用例启用每步限时
+    let move_timeout = rng.chance(50).then(|| MoveTimeout {
+        blocks: MOVE_TIMEOUT_BLOCKS,
+        penalty: if rng.chance(50) {
+            MoveTimeoutPenalty::LoseTry
+        } else {
+            MoveTimeoutPenalty::LoseGame
+        },
+    });
+    // 一半的用例将请求放入队列，按随机的顺序转发
+    let queued = rng.chance(50);
+    let mut builder = Scenario::builder()
+        .config(GameSessionConfig {
+            move_timeout,
+            max_hints: MAX_HINTS,
+            ..Default::default()
+        })
+        .seed(Some(seed));
+    if queued {
+        builder = builder.relay(RELAY);
+    }
+    let scenario = builder.build();
+
+    let mut queue = Queue::default();
+    // 用户发送的消息以及已收到回复的消息
+    let mut sent = Vec::new();
+    let mut replied = HashSet::new();
+    let mut unstarted = 0;
+    let mut steps = Vec::new();
+    let mut prev = Snapshot::read(&scenario);
+    loop {
+        // 步数用完后按随机的顺序转发队列中剩余的请求
+        let step = match (steps.len() < STEPS, queue.0.len()) {
+            (true, _) => next_step(&mut rng, &prev, &queue),
+            (false, 0) => break,
+            (false, len) => Step::Deliver {
+                user: queue.0[rng.below(len as u64) as usize].0,
+            },
+        };
+        let checked = run_step(&scenario, &mut queue, &step).and_then(|results| {
+            queue.collect(&results);
+            for log in results.iter().flat_map(RunResult::log) {
+                replied.extend(log.reply_to());
+            }
+            let res = match step {
+                Step::Send { .. } | Step::Forge { .. } => results.first(),
+                Step::Deliver { .. } | Step::Advance { .. } => None,
+            };
+            sent.extend(res.map(RunResult::sent_message_id));
+            let next = Snapshot::read(&scenario);
+            unstarted += users()
+                .filter(|user| {
+                    prev.session(*user).core_state().phase == Phase::Starting
+                        && next.stats(*user).games_played > prev.stats(*user).games_played
+                })
+                .count() as u32;
+            check_step(
+                &step,
+                res,
+                &prev,
+                &next,
+                queued.then_some(&queue),
+                unstarted,
+            )?;
+            prev = next;
+            Ok(())
+        });
+        steps.push(step);
+        if let Err(error) = checked {
+            return Err((steps, error));
+        }
+    }
+    // 所有请求都已回复后，用户的每条消息都收到了回复，没有消息一直等待
+    let unanswered: Vec<_> = sent.iter().filter(|id| !replied.contains(*id)).collect();
+    if !unanswered.is_empty() {
+        return Err((steps, format!("Messages without a reply: {unanswered:?}")));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_session_fuzz() {
+    let cases = env_u64("GWORD_FUZZ_CASES").unwrap_or(CASES);
+    let base_seed = env_u64("GWORD_FUZZ_SEED").unwrap_or(DEFAULT_SEED);
+    for case in 0..cases {
+        let seed = base_seed.wrapping_add(case);
+        if let Err((steps, error)) = run_case(seed) {
+            let trace = steps
+                .iter()
+                .enumerate()
+                .map(|(index, step)| format!("{index}: {step:?}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            panic!("{error}\nReproduce with GWORD_FUZZ_SEED={seed} GWORD_FUZZ_CASES=1\n{trace}");
+        }
+    }
+}
@@ -0,0 +1,1413 @@
+use gbot_io::{BotAction, BotInit, BotState, Skill};
+use gmastermind_io::{Feedback, Guess, TRIES_LIMIT as MASTERMIND_TRIES_LIMIT};
+use gsession_io::*;
+use gstd::{ActorId, Decode, Encode};
+use gtest::{constants::EXISTENTIAL_DEPOSIT, RunResult};
+use gword_core::{check_word_packed, seeded_random, WORD_PACKS};
+use gword_io::{
+    challenge_code_hash, challenge_commitment, Action, ChallengeTarget, Difficulty, Event,
+    WordStats, TIER_MIN_GAMES,
+};
+use gword_testing::*;
+
+// Mastermind 的游戏类型
+const MASTERMIND_GAME_TYPE: GameTypeId = 1;
+// 困难单词包
+const HARD_PACK: PackId = 3;
+// 用户ID，同时是管理员
+const USER: u64 = ADMIN;
+// 代理账户ID
+const DELEGATE: u64 = 60;
+// 好友ID
+const FRIEND: u64 = 70;
+// 中转账户ID，代替 Wordle 程序接收会话程序的请求
+const RELAY: u64 = 80;
+// 默认种子下第一局的单词
+const WORD: &str = "human";
+
+fn start() -> GameSessionAction {
+    GameSessionAction::StartGame
+}
+
+fn check(word: &str) -> GameSessionAction {
+    GameSessionAction::CheckWord {
+        word: word.to_string(),
+    }
+}
+
+fn hint(payment: HintPayment) -> GameSessionAction {
+    GameSessionAction::RequestHint { payment }
+}
+
+// 协议 V1 的猜词结果
+fn check_result(key_word: &str, word: &str) -> GameSessionEvent {
+    let (correct_positions, contained_in_word) = check_word_packed(key_word, word).into();
+    GameSessionEvent::CheckWordResult {
+        correct_positions,
+        contained_in_word,
+    }
+}
+
+#[test]
+fn test_win() {
+    Scenario::new()
+        .send(USER, start(), Expect::event(GameSessionEvent::StartSuccess))
+        // 游戏中不能开始新游戏
+        .send(USER, start(), Expect::panic("User is already in the game"))
+        .send(
+            USER,
+            check("house"),
+            Expect::event(check_result(WORD, "house")),
+        )
+        // 不是五个小写字母
+        .send(USER, check("caonima"), Expect::panic("Invalid word"))
+        .send(USER, check("Human"), Expect::panic("Invalid word"))
+        .send(
+            USER,
+            check(WORD),
+            Expect::event(GameSessionEvent::GameOver(GameStatus::Win)),
+        )
+        // 游戏结束后不能猜词
+        .send(USER, check(WORD), Expect::panic("User is not in the game"))
+        .send(
+            FRIEND,
+            check(WORD),
+            Expect::panic("User is not in the game"),
+        );
+}
+
+#[test]
+fn test_tries_limit() {
+    let scenario = Scenario::new();
+    scenario.send(USER, start(), Expect::ok());
+    for _ in 1..TRIES_LIMIT {
+        scenario.send(
+            USER,
+            check("house"),
+            Expect::event(check_result(WORD, "house")),
+        );
+    }
+    scenario
+        .send(
+            USER,
+            check("house"),
+            Expect::event(GameSessionEvent::GameOver(GameStatus::Lose)),
+        )
+        .status(USER, SessionStatus::GameOver(GameStatus::Lose));
+    assert_eq!(scenario.session_info(USER).tries, TRIES_LIMIT);
+}
+
+#[test]
+fn test_delayed_logic() {
+    Scenario::new()
+        .send(USER, start(), Expect::ok())
+        .advance(GAME_TIMEOUT_BLOCKS / 2, Expect::ok())
+        .status(USER, SessionStatus::WaitUserInput)
+        // 整局超时后失败
+        .advance(
+            GAME_TIMEOUT_BLOCKS / 2,
+            Expect::ok().to(USER, GameSessionEvent::GameOver(GameStatus::Lose)),
+        )
+        .status(USER, SessionStatus::GameOver(GameStatus::Lose));
+}
+
+#[test]
+fn test_session_key() {
+    let scenario = Scenario::new();
+    let create = || GameSessionAction::CreateSessionKey {
+        key: DELEGATE.into(),
+        duration: 100,
+        allowed_actions: vec![ActionKind::CheckWord],
+    };
+    let accept = || GameSessionAction::AcceptSessionKey { owner: USER.into() };
+    // 用户为代理账户提出只允许猜词的会话密钥
+    let expires_at = scenario.system().block_height() + 100;
+    scenario
+        .send(
+            USER,
+            create(),
+            Expect::event(GameSessionEvent::SessionKeyProposed {
+                key: DELEGATE.into(),
+                expires_at,
+            }),
+        )
+        // 代理账户接受之前不代表用户
+        .send(USER, start(), Expect::ok())
+        .send(
+            DELEGATE,
+            check("house"),
+            Expect::panic("User is not in the game"),
+        )
+        // 只能接受向自己提出的密钥
+        .send(
+            FRIEND,
+            accept(),
+            Expect::panic("Session key was not proposed"),
+        )
+        .send(
+            DELEGATE,
+            accept(),
+            Expect::event(GameSessionEvent::SessionKeyCreated {
+                key: DELEGATE.into(),
+                expires_at,
+            }),
+        )
+        // 未授权的动作失败
+        .send(DELEGATE, start(), Expect::panic("Session key"))
+        // 代理账户可以代表用户猜词
+        .send(
+            DELEGATE,
+            check("house"),
+            Expect::event(check_result(WORD, "house")),
+        )
+        // 代理账户不能再为其他账户创建密钥
+        .send(
+            DELEGATE,
+            GameSessionAction::CreateSessionKey {
+                key: FRIEND.into(),
+                duration: 100,
+                allowed_actions: vec![ActionKind::CheckWord],
+            },
+            Expect::panic("Session key cannot create keys"),
+        )
+        // 撤销后代理账户不再代表用户
+        .send(
+            USER,
+            GameSessionAction::DeleteSessionKey {
+                key: DELEGATE.into(),
+            },
+            Expect::ok(),
+        )
+        .send(
+            DELEGATE,
+            check(WORD),
+            Expect::panic("User is not in the game"),
+        );
+    assert_eq!(scenario.session_info(USER).tries, 1);
+
+    // 撤回尚未接受的提议后无法再接受
+    scenario
+        .send(USER, create(), Expect::ok())
+        .send(
+            USER,
+            GameSessionAction::DeleteSessionKey {
+                key: DELEGATE.into(),
+            },
+            Expect::ok(),
+        )
+        .send(
+            DELEGATE,
+            accept(),
+            Expect::panic("Session key was not proposed"),
+        );
+    // 未接受的提议到期后自动删除
+    scenario.send(USER, create(), Expect::ok());
+    assert_eq!(scenario.session_state().session_key_proposals.len(), 1);
+    scenario.advance(100, Expect::ok());
+    assert!(scenario.session_state().session_key_proposals.is_empty());
+}
+
+#[test]
+fn test_give_up_and_restart() {
+    let scenario = Scenario::new();
+    scenario
+        // 不在游戏中时无法放弃
+        .send(USER, GameSessionAction::GiveUp, Expect::failed())
+        // 放弃游戏并公开单词
+        .send(USER, start(), Expect::ok())
+        .send(
+            USER,
+            GameSessionAction::GiveUp,
+            Expect::event(GameSessionEvent::GaveUp {
+                word: Some(WORD.to_string()),
+            }),
+        )
+        .send(USER, check(WORD), Expect::panic("User is not in the game"))
+        // 游戏结束后 Restart 等同于 StartGame
+        .send(
+            USER,
+            GameSessionAction::Restart,
+            Expect::event(GameSessionEvent::StartSuccess),
+        )
+        // 游戏中 Restart 放弃当前游戏并开始新游戏
+        .send(
+            USER,
+            GameSessionAction::Restart,
+            Expect::event(GameSessionEvent::StartSuccess),
+        )
+        .send(USER, check("house"), Expect::ok());
+
+    let stats = scenario.player_stats(USER);
+    assert_eq!((stats.games_played, stats.losses, stats.gave_up), (2, 2, 2));
+}
+
+#[test]
+fn test_move_timeout() {
+    // 每步限时 10 个区块，超时失去一次尝试机会
+    let config = GameSessionConfig {
+        move_timeout: Some(MoveTimeout {
+            blocks: 10,
+            penalty: MoveTimeoutPenalty::LoseTry,
+        }),
+        ..Default::default()
+    };
+    Scenario::builder()
+        .config(config)
+        .build()
+        .send(USER, start(), Expect::ok())
+        .advance(
+            10,
+            Expect::ok().to(USER, GameSessionEvent::MoveTimedOut { tries: 1 }),
+        )
+        // 按时猜词后旧的计时器失效
+        .advance(5, Expect::ok())
+        .send(
+            USER,
+            check("house"),
+            Expect::event(check_result(WORD, "house")),
+        )
+        .advance(
+            5,
+            Expect::ok().not_to(USER, GameSessionEvent::MoveTimedOut { tries: 3 }),
+        )
+        // 剩余的尝试机会全部超时后游戏失败
+        .advance(
+            30,
+            Expect::ok()
+                .to(USER, GameSessionEvent::MoveTimedOut { tries: 4 })
+                .to(USER, GameSessionEvent::GameOver(GameStatus::Lose)),
+        );
+}
+
+#[test]
+fn test_move_timeout_after_hint() {
+    let price = EXISTENTIAL_DEPOSIT;
+    let config = GameSessionConfig {
+        move_timeout: Some(MoveTimeout {
+            blocks: 10,
+            penalty: MoveTimeoutPenalty::LoseTry,
+        }),
+        max_hints: 1,
+        hint_price: price,
+        ..Default::default()
+    };
+    let scenario = Scenario::builder().config(config).build();
+    scenario.system().mint_to(FRIEND, 10 * price);
+    // 付费提示不改变尝试次数，公开后重新计时，之前的计时器失效
+    scenario
+        .send(FRIEND, start(), Expect::ok())
+        .advance(5, Expect::ok())
+        .send_value(FRIEND, hint(HintPayment::Value), price, Expect::ok())
+        .advance(
+            5,
+            Expect::ok().not_to(FRIEND, GameSessionEvent::MoveTimedOut { tries: 1 }),
+        )
+        .advance(
+            10,
+            Expect::ok().to(FRIEND, GameSessionEvent::MoveTimedOut { tries: 1 }),
+        );
+}
+
+#[test]
+fn test_hints() {
+    // 每局最多两次提示，只能用尝试次数支付
+    let config = GameSessionConfig {
+        max_hints: 2,
+        ..Default::default()
+    };
+    let scenario = Scenario::builder().config(config).build();
+    scenario
+        .send(USER, start(), Expect::ok())
+        // 未设置价格时不能付费购买提示
+        .send(USER, hint(HintPayment::Value), Expect::failed())
+        .send(USER, hint(HintPayment::Try), Expect::ok())
+        .send(USER, hint(HintPayment::Try), Expect::ok())
+        // 超出每局提示上限
+        .send(USER, hint(HintPayment::Try), Expect::failed());
+
+    // 两次提示公开了两个不同的位置，并各消耗一次尝试
+    let session_info = scenario.session_info(USER);
+    assert_eq!(session_info.tries, 2);
+    assert_eq!(session_info.hints_used(), 2);
+    assert_eq!(session_info.known_positions().len(), 2);
+}
+
+#[test]
+fn test_hint_payment() {
+    let price = EXISTENTIAL_DEPOSIT;
+    let config = GameSessionConfig {
+        max_hints: 2,
+        hint_price: price,
+        ..Default::default()
+    };
+    let scenario = Scenario::builder().config(config).build();
+    scenario.system().mint_to(FRIEND, 10 * price);
+    scenario
+        .send(FRIEND, start(), Expect::ok())
+        // 用尝试机会支付时不能附带金额
+        .send_value(FRIEND, hint(HintPayment::Try), price, Expect::failed())
+        .send_value(FRIEND, hint(HintPayment::Value), price, Expect::ok());
+    let session_info = scenario.session_info(FRIEND);
+    assert_eq!((session_info.tries, session_info.hints_used()), (0, 1));
+    assert_eq!(scenario.session_state().hint_revenue, price);
+
+    // 只有管理员可以提取收入
+    scenario
+        .send(DELEGATE, GameSessionAction::Withdraw, Expect::failed())
+        .send(
+            USER,
+            GameSessionAction::Withdraw,
+            Expect::event(GameSessionEvent::Withdrawn { amount: price }),
+        )
+        .send(USER, GameSessionAction::Withdraw, Expect::failed());
+    assert_eq!(scenario.session_state().hint_revenue, 0);
+}
+
+#[test]
+fn test_rejected_request() {
+    let price = EXISTENTIAL_DEPOSIT;
+    let config = GameSessionConfig {
+        max_hints: 6,
+        hint_price: price,
+        ..Default::default()
+    };
+    let scenario = Scenario::builder().config(config).build();
+    scenario.system().mint_to(FRIEND, 10 * price);
+    scenario.send(FRIEND, start(), Expect::ok());
+    for _ in 0..5 {
+        scenario.send_value(FRIEND, hint(HintPayment::Value), price, Expect::ok());
+    }
+    // 所有字母都已知时 Wordle 程序拒绝请求，等待的消息收到失败事件并退回金额
+    scenario.send_value(
+        FRIEND,
+        hint(HintPayment::Value),
+        price,
+        Expect::event(GameSessionEvent::ActionFailed),
+    );
+    let session_info = scenario.session_info(FRIEND);
+    assert!(matches!(
+        session_info.session_status,
+        SessionStatus::WaitUserInput
+    ));
+    assert_eq!(session_info.hints_used(), 5);
+    assert_eq!(scenario.session_state().hint_revenue, 5 * price);
+    // 拒绝之后仍可以继续猜词
+    scenario.send(
+        FRIEND,
+        check(WORD),
+        Expect::event(GameSessionEvent::GameOver(GameStatus::Win)),
+    );
+}
+
+#[test]
+fn test_match() {
+    Scenario::new()
+        // 开始两个单词的比赛
+        .send(
+            USER,
+            GameSessionAction::StartMatch { words: 2 },
+            Expect::event(GameSessionEvent::StartSuccess),
+        )
+        // 第一个单词猜中后自动开始第二个单词
+        .send(
+            USER,
+            check(WORD),
+            Expect::event(GameSessionEvent::GameOver(GameStatus::Win))
+                .and(GameSessionEvent::NextWordStarted { word: 2 }),
+        )
+        // 比赛进行中不能开始新游戏
+        .send(USER, start(), Expect::failed())
+        // 放弃第二个单词后比赛结束
+        .send(
+            USER,
+            GameSessionAction::GiveUp,
+            Expect::event(GameSessionEvent::MatchOver(MatchInfo {
+                words: 2,
+                played: 2,
+                wins: 1,
+                total_tries: 1,
+            })),
+        );
+}
+
+#[test]
+fn test_engine_registry() {
+    let register = GameSessionAction::RegisterEngine {
+        game_type: 1,
+        program_id: WORDLE_ID.into(),
+    };
+    let scenario = Scenario::new();
+    scenario
+        // 未注册的游戏类型无法开始
+        .send(
+            USER,
+            GameSessionAction::StartEngineGame { game_type: 1 },
+            Expect::failed(),
+        )
+        // 只有管理员可以注册游戏程序
+        .send(DELEGATE, register.clone(), Expect::failed())
+        .send(
+            USER,
+            register,
+            Expect::event(GameSessionEvent::EngineRegistered {
+                game_type: 1,
+                program_id: WORDLE_ID.into(),
+            }),
+        )
+        // Wordle 不能被移除
+        .send(
+            USER,
+            GameSessionAction::RemoveEngine {
+                game_type: WORDLE_GAME_TYPE,
+            },
+            Expect::failed(),
+        )
+        .send(
+            USER,
+            GameSessionAction::RemoveEngine { game_type: 1 },
+            Expect::event(GameSessionEvent::EngineRemoved { game_type: 1 }),
+        );
+
+    assert_eq!(
+        scenario.session_state().engines,
+        vec![(WORDLE_GAME_TYPE, WORDLE_ID.into())]
+    );
+}
+
+#[test]
+fn test_mastermind() {
+    let make_move = |guess: Guess| GameSessionAction::MakeMove {
+        data: guess.encode(),
+    };
+    let scenario = Scenario::builder()
+        .engine(MASTERMIND_GAME_TYPE, "gmastermind")
+        .build();
+    scenario
+        // 通过游戏会话开始 Mastermind
+        .send(
+            USER,
+            GameSessionAction::StartEngineGame {
+                game_type: MASTERMIND_GAME_TYPE,
+            },
+            Expect::event(GameSessionEvent::StartSuccess),
+        )
+        // Mastermind 游戏中不能猜单词
+        .send(USER, check("house"), Expect::failed())
+        // 无效的颜色
+        .send(USER, make_move(Guess([0, 1, 2, 6])), Expect::failed());
+
+    // 用尽尝试次数后游戏结束
+    for _ in 0..MASTERMIND_TRIES_LIMIT {
+        scenario.send(USER, make_move(Guess([0, 0, 1, 1])), Expect::ok());
+    }
+    let session_info = scenario.session_info(USER);
+    assert!(matches!(
+        session_info.session_status,
+        SessionStatus::GameOver(..)
+    ));
+    // 每次行动的反馈都记录在历史中
+    for entry in &session_info.history {
+        let HistoryEntry::Move { feedback, .. } = entry else {
+            panic!("Unexpected history entry");
+        };
+        let feedback = Feedback::decode(&mut &feedback[..]).unwrap();
+        assert!(feedback.black + feedback.white <= 4);
+    }
+}
+
+#[test]
+fn test_hangman() {
+    let guess = |letter| GameSessionAction::GuessLetter { letter };
+    let scenario = Scenario::new();
+    scenario
+        .send(
+            USER,
+            GameSessionAction::StartHangman,
+            Expect::event(GameSessionEvent::HangmanStarted { word_length: 5 }),
+        )
+        // 猜字母游戏中不能猜整个单词
+        .send(USER, check(WORD), Expect::failed())
+        // 猜错的字母计入猜错次数
+        .send(
+            USER,
+            guess(b'z'),
+            Expect::event(GameSessionEvent::LetterResult {
+                letter: b'z',
+                positions: vec![],
+                wrong_guesses: 1,
+            }),
+        )
+        // 不能重复猜同一个字母
+        .send(USER, guess(b'z'), Expect::failed())
+        .send(
+            USER,
+            guess(b'h'),
+            Expect::event(GameSessionEvent::LetterResult {
+                letter: b'h',
+                positions: vec![0],
+                wrong_guesses: 1,
+            }),
+        );
+    // 猜中所有字母后获胜
+    for letter in [b'u', b'm', b'a'] {
+        scenario.send(USER, guess(letter), Expect::ok());
+    }
+    scenario.send(
+        USER,
+        guess(b'n'),
+        Expect::event(GameSessionEvent::GameOver(GameStatus::Win)),
+    );
+}
+
+// 将会话程序发给中转账户的请求转发给 Wordle 程序
+fn relay(scenario: &Scenario, res: RunResult) -> RunResult {
+    let request = res
+        .log()
+        .iter()
+        .find(|log| log.destination() == ActorId::from(RELAY))
+        .expect("No relayed request");
+    scenario.relay(request)
+}
+
+// 等待猜测的回复时重新发送被拒绝，回复到达后只记录一次猜测
+fn check_resend(
+    scenario: &Scenario,
+    user: u64,
+    start: GameSessionAction,
+    guess: GameSessionAction,
+) {
+    relay(scenario, scenario.try_send(user, start));
+    let pending = scenario.try_send(user, guess.clone());
+    scenario.send(user, guess, Expect::failed());
+    relay(scenario, pending);
+    let session_info = scenario.session_info(user);
+    assert_eq!((session_info.tries, session_info.history.len()), (1, 1));
+    assert!(matches!(
+        session_info.session_status,
+        SessionStatus::WaitUserInput
+    ));
+}
+
+#[test]
+fn test_resend_guess() {
+    // Wordle 程序保存猜测的模式中，重新发送的猜测会被执行两次
+    let scenario = Scenario::builder().relay(RELAY).build();
+    check_resend(
+        &scenario,
+        USER,
+        GameSessionAction::StartHangman,
+        GameSessionAction::GuessLetter { letter: b'h' },
+    );
+    check_resend(
+        &scenario,
+        FRIEND,
+        GameSessionAction::StartMultiGame {
+            boards: 2,
+            pack: DEFAULT_PACK,
+        },
+        check("house"),
+    );
+    check_resend(
+        &scenario,
+        DELEGATE,
+        GameSessionAction::StartAdversarial { pack: DEFAULT_PACK },
+        check("house"),
+    );
+}
+
+#[test]
+fn test_multi_board() {
+    let scenario = Scenario::new();
+    scenario
+        // 棋盘数只能是 2、4 或 8
+        .send(
+            USER,
+            GameSessionAction::StartMultiGame {
+                boards: 3,
+                pack: DEFAULT_PACK,
+            },
+            Expect::failed(),
+        )
+        // 单词包的单词数不能少于棋盘数
+        .send(
+            USER,
+            GameSessionAction::StartMultiGame {
+                boards: 4,
+                pack: DEFAULT_PACK,
+            },
+            Expect::event(GameSessionEvent::StartFailed),
+        )
+        .send(
+            USER,
+            GameSessionAction::StartMultiGame {
+                boards: 2,
+                pack: DEFAULT_PACK,
+            },
+            Expect::event(GameSessionEvent::StartSuccess),
+        )
+        // 多棋盘游戏中不能猜字母
+        .send(
+            USER,
+            GameSessionAction::GuessLetter { letter: b'h' },
+            Expect::failed(),
+        );
+
+    // 依次猜单词库中的所有单词，每个棋盘都会被猜中
+    let won = WORD_PACKS[DEFAULT_PACK as usize].words.iter().any(|word| {
+        matches!(
+            scenario.reply(USER, check(word)),
+            GameSessionEvent::GameOver(GameStatus::Win)
+        )
+    });
+    assert!(won);
+
+    // 两个棋盘都已记录为猜中
+    let session_info = scenario.session_info(USER);
+    let mut solved_boards = session_info.solved_boards();
+    solved_boards.sort_unstable();
+    assert_eq!(solved_boards, vec![0, 1]);
+    assert!(session_info.tries < session_info.mode.tries_limit().unwrap());
+
+    // 单词包只剩一个没有玩过的单词时重新开始，两个棋盘的单词仍然不同
+    scenario.send(
+        USER,
+        GameSessionAction::StartMultiGame {
+            boards: 2,
+            pack: DEFAULT_PACK,
+        },
+        Expect::event(GameSessionEvent::StartSuccess),
+    );
+    let GameSessionEvent::GaveUp { word: Some(words) } =
+        scenario.reply(USER, GameSessionAction::GiveUp)
+    else {
+        panic!("Unexpected event");
+    };
+    let words: Vec<&str> = words.split(',').collect();
+    assert_eq!(words.len(), 2);
+    assert_ne!(words[0], words[1]);
+}
+
+#[test]
+fn test_adversarial() {
+    Scenario::new()
+        // 不存在的单词包
+        .send(
+            USER,
+            GameSessionAction::StartAdversarial { pack: 99 },
+            Expect::event(GameSessionEvent::StartFailed),
+        )
+        .send(
+            USER,
+            GameSessionAction::StartAdversarial { pack: DEFAULT_PACK },
+            Expect::event(GameSessionEvent::StartSuccess),
+        )
+        // 对抗模式不提供提示
+        .send(USER, hint(HintPayment::Try), Expect::failed())
+        // 每组只有一个候选单词时，保留正确位置最少的一组：human
+        .send(
+            USER,
+            check("house"),
+            Expect::event(check_result("human", "house")),
+        )
+        // 只剩一个候选单词，猜中即获胜
+        .send(
+            USER,
+            check("human"),
+            Expect::event(GameSessionEvent::GameOver(GameStatus::Win)),
+        );
+}
+
+#[test]
+fn test_challenge() {
+    let salt = [7; 32];
+    // 创建时只发送单词和盐的承诺
+    let create = |word: &str| Action::CreateChallenge {
+        commitment: challenge_commitment(word, &salt),
+        target: ChallengeTarget::Friend(FRIEND.into()),
+    };
+    let open = |challenge_id, word: &str| Action::OpenChallenge {
+        challenge_id,
+        word: word.to_string(),
+        salt,
+    };
+    let start_challenge = |challenge_id| GameSessionAction::StartChallenge {
+        challenge_id,
+        code: None,
+    };
+    Scenario::new()
+        .send_to(
+            WORDLE_ID,
+            USER,
+            create("horse"),
+            0,
+            Expect::event(Event::ChallengeCreated {
+                creator: USER.into(),
+                challenge_id: 0,
+            }),
+        )
+        // 创建者公开单词之前不能参加挑战
+        .send(
+            FRIEND,
+            start_challenge(0),
+            Expect::event(GameSessionEvent::StartFailed),
+        )
+        // 只有创建者能公开单词，单词必须与承诺一致
+        .send_to(
+            WORDLE_ID,
+            FRIEND,
+            open(0, "horse"),
+            0,
+            Expect::panic("Only the creator can open the challenge"),
+        )
+        .send_to(
+            WORDLE_ID,
+            USER,
+            open(0, "house"),
+            0,
+            Expect::panic("The word does not match the commitment"),
+        )
+        .send_to(
+            WORDLE_ID,
+            USER,
+            open(0, "horse"),
+            0,
+            Expect::event(Event::ChallengeOpened {
+                creator: USER.into(),
+                challenge_id: 0,
+            }),
+        )
+        // 公开的单词必须在单词库中
+        .send_to(WORDLE_ID, USER, create("zebra"), 0, Expect::ok())
+        .send_to(WORDLE_ID, USER, open(1, "zebra"), 0, Expect::failed())
+        // 玩家不能绕过会话程序参加挑战
+        .send_to(
+            WORDLE_ID,
+            FRIEND,
+            Action::StartChallenge {
+                user: FRIEND.into(),
+                challenge_id: 0,
+                code: None,
+            },
+            0,
+            Expect::panic("Only the session program can act for players"),
+        )
+        // 其他玩家不能参加指定好友的挑战
+        .send(
+            DELEGATE,
+            start_challenge(0),
+            Expect::event(GameSessionEvent::StartFailed),
+        )
+        .status(DELEGATE, SessionStatus::Init)
+        .send(
+            FRIEND,
+            start_challenge(0),
+            Expect::event(GameSessionEvent::ChallengeStarted {
+                challenge_id: 0,
+                creator: USER.into(),
+                commitment: challenge_commitment("horse", &salt),
+            }),
+        )
+        // 猜中后创建者收到结果，玩家收到单词和盐，可以校验承诺
+        .send(
+            FRIEND,
+            check("horse"),
+            Expect::event(GameSessionEvent::GameOver(GameStatus::Win))
+                .to(
+                    USER,
+                    GameSessionEvent::ChallengeCompleted {
+                        challenge_id: 0,
+                        player: FRIEND.into(),
+                        status: GameStatus::Win,
+                        tries: 1,
+                    },
+                )
+                .and(GameSessionEvent::ChallengeRevealed {
+                    challenge_id: 0,
+                    word: "horse".to_string(),
+                    salt,
+                }),
+        )
+        // 每个挑战只能玩一次
+        .send(
+            FRIEND,
+            start_challenge(0),
+            Expect::event(GameSessionEvent::StartFailed),
+        );
+}
+
+#[test]
+fn test_challenge_code() {
+    // 创建时只发送邀请码的哈希，持有邀请码的任何玩家都可以参加
+    let salt = [7; 32];
+    let create = Action::CreateChallenge {
+        commitment: challenge_commitment("horse", &salt),
+        target: ChallengeTarget::Code(challenge_code_hash(1234)),
+    };
+    let open = Action::OpenChallenge {
+        challenge_id: 0,
+        word: "horse".to_string(),
+        salt,
+    };
+    let start_challenge = |code| GameSessionAction::StartChallenge {
+        challenge_id: 0,
+        code,
+    };
+    Scenario::new()
+        .send_to(WORDLE_ID, USER, create, 0, Expect::ok())
+        .send_to(WORDLE_ID, USER, open, 0, Expect::ok())
+        .send(
+            FRIEND,
+            start_challenge(None),
+            Expect::event(GameSessionEvent::StartFailed),
+        )
+        .send(
+            FRIEND,
+            start_challenge(Some(4321)),
+            Expect::event(GameSessionEvent::StartFailed),
+        )
+        .send(
+            DELEGATE,
+            start_challenge(Some(1234)),
+            Expect::event(GameSessionEvent::ChallengeStarted {
+                challenge_id: 0,
+                creator: USER.into(),
+                commitment: challenge_commitment("horse", &salt),
+            }),
+        );
+}
+
+#[test]
+fn test_wordle_source() {
+    // 玩家不能绕过会话程序直接操作自己或他人的游戏
+    let user: ActorId = USER.into();
+    let scenario = Scenario::new();
+    scenario.send(USER, start(), Expect::ok());
+    for action in [
+        Action::StartGame {
+            user,
+            pack: DEFAULT_PACK,
+        },
+        Action::CheckWord {
+            user,
+            word: WORD.to_string(),
+        },
+        Action::RevealWord { user },
+        // 伪造的结果会污染单词统计
+        Action::RecordResult {
+            user,
+            won: true,
+            tries: 1,
+        },
+        Action::RevealLetter {
+            user,
+            known_positions: Vec::new(),
+        },
+    ] {
+        scenario.send_to(
+            WORDLE_ID,
+            USER,
+            action,
+            0,
+            Expect::panic("Only the session program can act for players"),
+        );
+    }
+    // 游戏没有受到影响
+    scenario.send(
+        USER,
+        check(WORD),
+        Expect::event(GameSessionEvent::GameOver(GameStatus::Win)),
+    );
+}
+
+#[test]
+fn test_no_repeat_words() {
+    let scenario = Scenario::new();
+    let user: ActorId = USER.into();
+    let mut words = Vec::new();
+    for unseen in (0..3).rev() {
+        scenario.send(USER, start(), Expect::ok());
+        let GameSessionEvent::GaveUp { word: Some(word) } =
+            scenario.reply(USER, GameSessionAction::GiveUp)
+        else {
+            panic!("Unexpected event");
+        };
+        words.push(word);
+
+        // 状态中显示尚未玩过的单词数
+        let state = scenario.wordle_state();
+        assert_eq!(state.packs[DEFAULT_PACK as usize].size, 3);
+        assert_eq!(state.unseen_words, vec![(user, DEFAULT_PACK, unseen)]);
+    }
+
+    // 单词库用完之前不会重复
+    words.sort();
+    words.dedup();
+    assert_eq!(words.len(), 3);
+
+    // 用完后重新开始
+    scenario.send(USER, start(), Expect::ok());
+    assert_eq!(
+        scenario.wordle_state().unseen_words,
+        vec![(user, DEFAULT_PACK, 2)]
+    );
+}
+
+#[test]
+fn test_seed() {
+    // 相同的种子得到相同的单词，第一局的单词由种子决定
+    let words = WORD_PACKS[DEFAULT_PACK as usize].words;
+    for seed in [DEFAULT_SEED, 7] {
+        let scenario = Scenario::builder().seed(Some(seed)).build();
+        scenario.send(USER, start(), Expect::ok());
+        let expected = words[(seeded_random(seed, 0) % words.len() as u64) as usize];
+        scenario.send(
+            USER,
+            GameSessionAction::GiveUp,
+            Expect::event(GameSessionEvent::GaveUp {
+                word: Some(expected.to_string()),
+            }),
+        );
+    }
+    assert_eq!(
+        words[(seeded_random(DEFAULT_SEED, 0) % words.len() as u64) as usize],
+        WORD
+    );
+
+    // 不指定种子时使用链上随机数
+    let scenario = Scenario::builder().seed(None).build();
+    scenario.send(USER, start(), Expect::ok());
+    assert!(scenario.solve(USER) <= TRIES_LIMIT);
+}
+
+#[test]
+fn test_word_packs() {
+    // 状态中列出可选的单词包
+    let scenario = Scenario::new();
+    let state = scenario.wordle_state();
+    assert_eq!(state.packs.len(), 4);
+    assert_eq!(state.packs[HARD_PACK as usize].name, "hard");
+    assert_eq!(state.packs[HARD_PACK as usize].difficulty, Difficulty::Hard);
+
+    scenario
+        // 不存在的单词包
+        .send(
+            USER,
+            GameSessionAction::StartGameWithPack { pack: 99 },
+            Expect::event(GameSessionEvent::StartFailed),
+        )
+        .send(
+            USER,
+            GameSessionAction::StartGameWithPack { pack: HARD_PACK },
+            Expect::event(GameSessionEvent::StartSuccess),
+        )
+        .send(USER, GameSessionAction::GiveUp, Expect::ok());
+
+    // 统计按单词包记录
+    assert_eq!(
+        scenario.player_stats(USER).packs,
+        vec![(
+            HARD_PACK,
+            PackStats {
+                games_played: 1,
+                wins: 0,
+            }
+        )]
+    );
+}
+
+#[test]
+fn test_word_stats() {
+    let scenario = Scenario::new();
+    // 第一局放弃，第二局猜中
+    scenario
+        .send(USER, start(), Expect::ok())
+        .send(USER, GameSessionAction::GiveUp, Expect::ok())
+        .send(USER, start(), Expect::ok());
+    let tries = scenario.solve(USER) as u32;
+
+    let state = scenario.wordle_state();
+    assert_eq!(state.word_stats.len(), 2);
+    let (_, _, won) = state
+        .word_stats
+        .iter()
+        .find(|(_, _, stats)| stats.wins == 1)
+        .unwrap();
+    assert_eq!(won.served, 1);
+    assert_eq!(won.average_tries_x100(), Some(tries * 100));
+    let (_, word, lost) = state
+        .word_stats
+        .iter()
+        .find(|(_, _, stats)| stats.losses == 1)
+        .unwrap();
+    assert_eq!(word, WORD);
+    assert_eq!(lost.win_rate(), Some(0));
+    // 局数不足时不划分难度
+    assert_eq!(lost.tier(), None);
+
+    let stats = WordStats {
+        served: TIER_MIN_GAMES,
+        wins: TIER_MIN_GAMES / 2,
+        losses: TIER_MIN_GAMES / 2,
+        total_tries: TIER_MIN_GAMES,
+    };
+    assert_eq!(stats.tier(), Some(Difficulty::Medium));
+}
+
+#[test]
+fn test_restart_word_stats() {
+    let scenario = Scenario::new();
+    // 重新开始时放弃的游戏按失败计入单词统计
+    scenario
+        .send(USER, start(), Expect::ok())
+        .send(USER, check("house"), Expect::ok())
+        .send(USER, GameSessionAction::Restart, Expect::ok());
+
+    let state = scenario.wordle_state();
+    let (_, _, lost) = state
+        .word_stats
+        .iter()
+        .find(|(_, word, _)| word == WORD)
+        .unwrap();
+    assert_eq!((lost.served, lost.wins, lost.losses), (1, 0, 1));
+    assert_eq!(state.word_stats.len(), 2);
+}
+
+#[test]
+fn test_analytics() {
+    let scenario = Scenario::new();
+    scenario
+        // 放弃一局
+        .send(USER, start(), Expect::ok())
+        .send(USER, GameSessionAction::GiveUp, Expect::ok())
+        // 猜错一次后超时一局
+        .send(USER, start(), Expect::ok())
+        .send(USER, check("zzzzz"), Expect::ok())
+        .advance(GAME_TIMEOUT_BLOCKS, Expect::ok())
+        // 另一名玩家的游戏仍在进行中
+        .send(FRIEND, start(), Expect::ok());
+
+    let analytics = scenario.analytics();
+    let totals = &analytics.totals;
+    assert_eq!((totals.started, totals.finished, totals.won), (3, 2, 0));
+    assert_eq!(
+        (
+            totals.abandoned,
+            totals.lost_by_timeout,
+            totals.lost_by_tries
+        ),
+        (1, 1, 0)
+    );
+    assert_eq!(totals.average_tries_x100(), Some(50));
+    assert_eq!(analytics.active_games(), 1);
+    // 所有游戏都在第一个周期内
+    assert_eq!(analytics.epochs.len(), 1);
+    assert_eq!(analytics.epochs[0].0, 0);
+    assert_eq!(&analytics.epochs[0].1, totals);
+}
+
+#[test]
+fn test_wordle_protocol() {
+    // 会话程序与 Wordle 程序的消息编码必须一致
+    let user: ActorId = USER.into();
+    assert_eq!(
+        Action::StartMultiGame {
+            user,
+            boards: 2,
+            pack: DEFAULT_PACK
+        }
+        .encode(),
+        WordleAction::StartMultiGame {
+            user,
+            boards: 2,
+            pack: DEFAULT_PACK
+        }
+        .encode()
+    );
+    assert_eq!(
+        Action::GuessLetter { user, letter: b'h' }.encode(),
+        WordleAction::GuessLetter { user, letter: b'h' }.encode()
+    );
+    let event = Event::LetterRevealed {
+        user,
+        position: 1,
+        letter: b'u',
+    };
+    assert!(matches!(
+        WordleEvent::decode(&mut &event.encode()[..]),
+        Ok(WordleEvent::LetterRevealed {
+            position: 1,
+            letter: b'u',
+            ..
+        })
+    ));
+    let event = Event::BoardsChecked {
+        user,
+        boards: vec![],
+    };
+    assert!(matches!(
+        WordleEvent::decode(&mut &event.encode()[..]),
+        Ok(WordleEvent::BoardsChecked { .. })
+    ));
+}
+
+#[test]
+fn test_protocol_negotiation() {
+    let scenario = Scenario::new();
+    scenario
+        // 没有共同支持的版本
+        .send(
+            USER,
+            GameSessionAction::NegotiateProtocol { versions: vec![] },
+            Expect::failed(),
+        )
+        // 选择双方都支持的最高版本
+        .send(
+            USER,
+            GameSessionAction::NegotiateProtocol {
+                versions: ProtocolVersion::SUPPORTED.to_vec(),
+            },
+            Expect::event(GameSessionEvent::ProtocolSelected {
+                version: ProtocolVersion::V2,
+            }),
+        )
+        // 协议 V2 返回紧凑反馈，对抗模式的结果是确定的：human
+        .send(
+            USER,
+            GameSessionAction::StartAdversarial { pack: DEFAULT_PACK },
+            Expect::ok(),
+        )
+        .send(
+            USER,
+            check("house"),
+            Expect::event(GameSessionEvent::CheckWordResultV2 {
+                feedback: check_word_packed("human", "house"),
+            }),
+        );
+    // 协议版本保存在会话信息中，开始新游戏后仍然有效
+    assert_eq!(scenario.session_info(USER).protocol, ProtocolVersion::V2);
+
+    // 未协商的用户仍然收到 V1 结果
+    scenario
+        .send(
+            FRIEND,
+            GameSessionAction::StartAdversarial { pack: DEFAULT_PACK },
+            Expect::ok(),
+        )
+        .send(
+            FRIEND,
+            check("house"),
+            Expect::event(check_result("human", "house")),
+        );
+}
+
+#[test]
+fn test_bot() {
+    let scenario = Scenario::new();
+    let bot = scenario.deploy(
+        "gbot",
+        BotInit {
+            session_program_id: SESSION_ID.into(),
+            skill: Skill::Expert,
+        },
+    );
+    let play = |pack, games| BotAction::Play { pack, games };
+
+    // 专家水平在每个单词包中都能在尝试次数限制内猜中
+    for pack in 0..WORD_PACKS.len() as PackId {
+        for _ in 0..5 {
+            scenario.send_to(bot, USER, play(pack, 10), 0, Expect::ok());
+        }
+    }
+    let state: BotState = scenario.program(bot).read_state(b"").unwrap();
+    assert_eq!(state.requester, None);
+    let games = 50 * WORD_PACKS.len() as u32;
+    assert_eq!((state.stats.games_played, state.stats.wins), (games, games));
+
+    // 机器人的对局与普通用户一样计入统计
+    assert_eq!(scenario.player_stats(bot).games_played, games);
+
+    scenario
+        // 只有部署者可以修改水平
+        .send_to(
+            bot,
+            FRIEND,
+            BotAction::SetSkill(Skill::Novice),
+            0,
+            Expect::failed(),
+        )
+        .send_to(
+            bot,
+            USER,
+            BotAction::SetSkill(Skill::Novice),
+            0,
+            Expect::ok(),
+        )
+        // 无效的单词包
+        .send_to(bot, USER, play(99, 1), 0, Expect::failed())
+        // 新手水平随机猜测，所有局都会结束
+        .send_to(bot, FRIEND, play(HARD_PACK, 20), 0, Expect::ok());
+    let state: BotState = scenario.program(bot).read_state(b"").unwrap();
+    assert_eq!(state.stats.games_played, games + 20);
+}
+
+// 用模拟程序暂停回复，经过 SessionStatus 的每个状态变化。ReplyReceived 和 EngineReplyReceived
+// 只存在于收到回复到被唤醒的消息继续执行之间，每次从等待回复回到 WaitUserInput 都会经过
+#[test]
+fn test_session_status() {
+    let wordle = FakeWordle::new(WORD);
+    let stall = wordle.stall.clone();
+    let engine = FakeEngine::new(EngineResult::Continue);
+    let engine_stall = engine.stall.clone();
+    let config = GameSessionConfig {
+        max_hints: 1,
+        ..Default::default()
+    };
+    let scenario = Scenario::builder()
+        .config(config)
+        .fake_wordle(wordle)
+        .fake_engine(1, engine)
+        .fake_engine(2, FakeEngine::new(EngineResult::Win))
+        .build();
+    let lose = GameSessionEvent::GameOver(GameStatus::Lose);
+    // 超时结束游戏时等待回复的消息收到失败事件
+    let failed = GameSessionEvent::ActionFailed;
+    let timeout = Expect::ok().to(USER, lose.clone()).to(USER, failed);
+
+    // WaitWordleStartReply -> Init：模拟程序不支持挑战，拒绝开始
+    scenario
+        .send(
+            USER,
+            GameSessionAction::StartChallenge {
+                challenge_id: 0,
+                code: None,
+            },
+            Expect::event(GameSessionEvent::StartFailed),
+        )
+        .status(USER, SessionStatus::Init);
+
+    // Init -> WaitWordleStartReply -> WaitUserInput，等待开始回复时可以重新开始，之前的消息收到失败事件
+    stall.pause();
+    scenario
+        .send(USER, start(), Expect::ok())
+        .status(USER, SessionStatus::WaitWordleStartReply);
+    stall.resume();
+    scenario
+        .send(
+            USER,
+            start(),
+            Expect::event(GameSessionEvent::StartSuccess).and(GameSessionEvent::StartFailed),
+        )
+        .status(USER, SessionStatus::WaitUserInput);
+
+    // WaitUserInput -> WaitWordleCheckWordReply -> WaitUserInput，等待时可以重新猜词
+    stall.pause();
+    scenario
+        .send(USER, check("house"), Expect::ok())
+        .status(USER, SessionStatus::WaitWordleCheckWordReply);
+    stall.resume();
+    scenario
+        .send(
+            USER,
+            check("house"),
+            Expect::event(check_result(WORD, "house")).and(GameSessionEvent::ActionFailed),
+        )
+        .status(USER, SessionStatus::WaitUserInput);
+
+    // WaitUserInput -> WaitWordleHintReply，等待时不能再次请求，整局超时后 -> GameOver
+    stall.pause();
+    scenario
+        .send(USER, hint(HintPayment::Try), Expect::ok())
+        .status(USER, SessionStatus::WaitWordleHintReply)
+        .send(USER, hint(HintPayment::Try), Expect::failed())
+        .send(USER, GameSessionAction::GiveUp, Expect::failed())
+        .advance(GAME_TIMEOUT_BLOCKS, timeout.clone())
+        .status(USER, SessionStatus::GameOver(GameStatus::Lose));
+    stall.resume();
+
+    // GameOver -> WaitUserInput -> WaitWordleRevealReply，整局超时后 -> GameOver
+    scenario.send(USER, start(), Expect::ok());
+    stall.pause();
+    scenario
+        .send(USER, GameSessionAction::GiveUp, Expect::ok())
+        .status(USER, SessionStatus::WaitWordleRevealReply)
+        .advance(GAME_TIMEOUT_BLOCKS, timeout.clone())
+        .status(USER, SessionStatus::GameOver(GameStatus::Lose));
+    stall.resume();
+
+    // WaitUserInput -> GameOver(Win)
+    scenario
+        .send(USER, start(), Expect::ok())
+        .send(
+            USER,
+            check(WORD),
+            Expect::event(GameSessionEvent::GameOver(GameStatus::Win)),
+        )
+        .status(USER, SessionStatus::GameOver(GameStatus::Win));
+
+    // 比赛中猜中第一个单词后 -> WaitWordleNextWordReply，整局超时后比赛结束
+    scenario.send(
+        USER,
+        GameSessionAction::StartMatch { words: 2 },
+        Expect::ok(),
+    );
+    stall.pause_after(1);
+    scenario
+        .send(USER, check(WORD), Expect::ok())
+        .status(USER, SessionStatus::WaitWordleNextWordReply)
+        .send(USER, check(WORD), Expect::failed())
+        .advance(
+            GAME_TIMEOUT_BLOCKS,
+            Expect::ok().to(
+                USER,
+                GameSessionEvent::MatchOver(MatchInfo {
+                    words: 2,
+                    played: 2,
+                    wins: 1,
+                    total_tries: 1,
+                }),
+            ),
+        )
+        .status(USER, SessionStatus::GameOver(GameStatus::Lose));
+    stall.resume();
+
+    // GameOver -> WaitEngineStartReply -> WaitUserInput，等待开始回复时可以重新开始
+    let start_engine = |game_type| GameSessionAction::StartEngineGame { game_type };
+    let make_move = GameSessionAction::MakeMove { data: vec![0] };
+    engine_stall.pause();
+    scenario
+        .send(USER, start_engine(1), Expect::ok())
+        .status(USER, SessionStatus::WaitEngineStartReply);
+    engine_stall.resume();
+    scenario
+        .send(
+            USER,
+            start_engine(1),
+            Expect::event(GameSessionEvent::StartSuccess),
+        )
+        .status(USER, SessionStatus::WaitUserInput)
+        .send(
+            USER,
+            make_move.clone(),
+            Expect::event(GameSessionEvent::MoveResult { data: vec![0] }),
+        );
+
+    // WaitUserInput -> WaitEngineMoveReply，等待时不能再次行动，整局超时后 -> GameOver
+    engine_stall.pause();
+    scenario
+        .send(USER, make_move.clone(), Expect::ok())
+        .status(USER, SessionStatus::WaitEngineMoveReply)
+        .send(USER, make_move.clone(), Expect::failed())
+        .advance(GAME_TIMEOUT_BLOCKS, timeout)
+        .status(USER, SessionStatus::GameOver(GameStatus::Lose));
+    engine_stall.resume();
+
+    // 游戏程序判定胜利 -> GameOver(Win)
+    scenario
+        .send(USER, start_engine(2), Expect::ok())
+        .send(
+            USER,
+            make_move,
+            Expect::event(GameSessionEvent::GameOver(GameStatus::Win)),
+        )
+        .status(USER, SessionStatus::GameOver(GameStatus::Win));
+}